@@ -1,13 +1,15 @@
+use iroha_client_no_std::bridge::BridgeDefinitionId;
 use iroha_client_no_std::crypto as iroha_crypto;
+use iroha_client_no_std::prelude as iroha;
 use sc_service::ChainType;
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
-use sp_core::{crypto::AccountId32, sr25519, Pair, Public};
+use sp_core::{crypto::AccountId32, ed25519, sr25519, Pair, Public};
 use sp_finality_grandpa::AuthorityId as GrandpaId;
 use sp_runtime::sp_std::convert::TryFrom;
 use sp_runtime::traits::{IdentifyAccount, Verify};
 use substrate_iroha_bridge_runtime::{
-    AccountId, AuraConfig, BalancesConfig, DOTConfig, GenesisConfig, GrandpaConfig,
-    IrohaBridgeConfig, KSMConfig, Signature, SudoConfig, SystemConfig, XORConfig, WASM_BINARY,
+    AccountId, AuraConfig, BalancesConfig, GenesisConfig, GrandpaConfig, IrohaBridgeConfig,
+    Signature, SudoConfig, SystemConfig, TreasuryConfig, WASM_BINARY,
 };
 
 // Note this is the URL for the telemetry server
@@ -38,6 +40,30 @@ pub fn authority_keys_from_seed(s: &str) -> (AuraId, GrandpaId) {
     (get_from_seed::<AuraId>(s), get_from_seed::<GrandpaId>(s))
 }
 
+/// Iroha public key of the root account, also used by the local Iroha peer.
+const IROHA_ROOT_KEY: [u8; 32] = [
+    52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243, 67, 8, 115, 247,
+    189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
+];
+
+/// Helper function to generate a bridge authority with its Iroha key from seed
+pub fn bridge_authority_from_seed(s: &str) -> (AccountId, iroha_crypto::PublicKey) {
+    (
+        get_account_id_from_seed::<sr25519::Public>(s),
+        iroha_crypto::PublicKey::try_from(get_from_seed::<ed25519::Public>(s).0.to_vec())
+            .expect("ed25519 public keys are valid Iroha keys; qed"),
+    )
+}
+
+/// The bridge authority signing with the Iroha root key
+pub fn iroha_root_authority() -> (AccountId, iroha_crypto::PublicKey) {
+    (
+        AccountId32::from(IROHA_ROOT_KEY),
+        iroha_crypto::PublicKey::try_from(IROHA_ROOT_KEY.to_vec())
+            .expect("static values are valid; qed"),
+    )
+}
+
 pub fn development_config() -> ChainSpec {
     ChainSpec::from_genesis(
         "Development",
@@ -52,16 +78,14 @@ pub fn development_config() -> ChainSpec {
                     get_account_id_from_seed::<sr25519::Public>("Bob"),
                     get_account_id_from_seed::<sr25519::Public>("Alice//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Bob//stash"),
-                    AccountId32::from([
-                        52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243,
-                        67, 8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
-                    ]),
+                    AccountId32::from(IROHA_ROOT_KEY),
+                ],
+                vec![
+                    bridge_authority_from_seed("Alice"),
+                    bridge_authority_from_seed("Bob"),
+                    iroha_root_authority(),
                 ],
-                vec![iroha_crypto::PublicKey::try_from(vec![
-                    52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243, 67,
-                    8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
-                ])
-                .unwrap()],
+                vec![iroha_crypto::PublicKey::try_from(IROHA_ROOT_KEY.to_vec()).unwrap()],
                 true,
             )
         },
@@ -98,16 +122,14 @@ pub fn local_testnet_config() -> ChainSpec {
                     get_account_id_from_seed::<sr25519::Public>("Dave//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Eve//stash"),
                     get_account_id_from_seed::<sr25519::Public>("Ferdie//stash"),
-                    AccountId32::from([
-                        52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243,
-                        67, 8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
-                    ]),
+                    AccountId32::from(IROHA_ROOT_KEY),
+                ],
+                vec![
+                    bridge_authority_from_seed("Alice"),
+                    bridge_authority_from_seed("Bob"),
+                    iroha_root_authority(),
                 ],
-                vec![iroha_crypto::PublicKey::try_from(vec![
-                    52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243, 67,
-                    8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
-                ])
-                .unwrap()],
+                vec![iroha_crypto::PublicKey::try_from(IROHA_ROOT_KEY.to_vec()).unwrap()],
                 true,
             )
         },
@@ -123,6 +145,7 @@ fn testnet_genesis(
     initial_authorities: Vec<(AuraId, GrandpaId)>,
     root_key: AccountId,
     endowed_accounts: Vec<AccountId>,
+    bridge_authorities: Vec<(AccountId, iroha_crypto::PublicKey)>,
     iroha_peers: Vec<iroha_crypto::PublicKey>,
     _enable_println: bool,
 ) -> GenesisConfig {
@@ -144,26 +167,6 @@ fn testnet_genesis(
                 .collect(),
         }),
         sudo: Some(SudoConfig { key: root_key }),
-        balances_Instance1: Some(XORConfig {
-            balances: endowed_accounts
-				.iter()
-				.cloned()
-				// .filter(|x| {
-				// 	x != &AccountId32::from([
-				// 		52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243,
-				// 		67, 8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
-				// 	])
-				// })
-				// .map(|k| (k, 1 << 60))
-				.map(|k| (k, 0))
-				.collect(),
-        }),
-        balances_Instance2: Some(DOTConfig {
-            balances: endowed_accounts.iter().cloned().map(|k| (k, 0)).collect(),
-        }),
-        balances_Instance3: Some(KSMConfig {
-            balances: endowed_accounts.iter().cloned().map(|k| (k, 0)).collect(),
-        }),
         balances: Some(BalancesConfig {
             balances: endowed_accounts
                 .iter()
@@ -171,9 +174,23 @@ fn testnet_genesis(
                 .map(|k| (k, 1 << 60))
                 .collect(),
         }),
+        treasury: Some(TreasuryConfig {
+            assets: vec![
+                (0, b"XOR".to_vec(), b"global".to_vec(), 0, 0),
+                (1, b"DOT".to_vec(), b"polkadot".to_vec(), 0, 0),
+                (2, b"KSM".to_vec(), b"polkadot".to_vec(), 0, 0),
+                // the native currency, lock-and-release
+                (3, b"UNIT".to_vec(), b"polkadot".to_vec(), 0, 0),
+            ],
+            balances: vec![],
+        }),
         iroha_bridge: Some(IrohaBridgeConfig {
-            authorities: endowed_accounts.clone(),
+            authorities: bridge_authorities,
             iroha_peers,
+            iroha_endpoint: b"http://127.0.0.1:7878".to_vec(),
+            threshold: 1,
+            bridge_account: Some(iroha::AccountId::new("bridge", "polkadot")),
+            bridge_definition: Some(BridgeDefinitionId::new("polkadot")),
         }),
     }
 }
@@ -1,5 +1,6 @@
 use sc_cli::{RunCmd, Subcommand};
 use structopt::StructOpt;
+use substrate_iroha_bridge_runtime::iroha_bridge;
 
 #[derive(Debug, StructOpt)]
 pub struct Cli {
@@ -8,4 +9,20 @@ pub struct Cli {
 
     #[structopt(flatten)]
     pub run: RunCmd,
+
+    /// URL of the Iroha peer used by the bridge offchain worker, e.g. `http://127.0.0.1:7878`.
+    /// Overrides the on-chain default.
+    #[structopt(long = "iroha-endpoint", parse(try_from_str = parse_iroha_endpoint))]
+    pub iroha_endpoint: Option<String>,
+}
+
+fn parse_iroha_endpoint(endpoint: &str) -> Result<String, String> {
+    if iroha_bridge::is_valid_iroha_endpoint(endpoint.as_bytes()) {
+        Ok(endpoint.into())
+    } else {
+        Err(format!(
+            "`{}` is not an http(s) URL of the form `http://host[:port][/path]`",
+            endpoint
+        ))
+    }
 }
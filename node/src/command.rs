@@ -71,9 +71,10 @@ pub fn run() -> sc_cli::Result<()> {
         }
         None => {
             let runner = cli.create_runner(&cli.run)?;
+            let iroha_endpoint = cli.iroha_endpoint.clone();
             runner.run_node(
                 service::new_light,
-                service::new_full,
+                |config| service::new_full(config, iroha_endpoint),
                 substrate_iroha_bridge_runtime::VERSION,
             )
         }
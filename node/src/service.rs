@@ -88,7 +88,13 @@ macro_rules! new_full_start {
 }
 
 /// Builds a new service for a full client.
-pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceError> {
+///
+/// `iroha_endpoint`, if given, is written to the offchain local storage where the Iroha bridge
+/// offchain worker picks it up instead of the on-chain default.
+pub fn new_full(
+    config: Configuration,
+    iroha_endpoint: Option<String>,
+) -> Result<impl AbstractService, ServiceError> {
     let role = config.role.clone();
     let force_authoring = config.force_authoring;
     let name = config.network.node_name.clone();
@@ -101,6 +107,20 @@ pub fn new_full(config: Configuration) -> Result<impl AbstractService, ServiceEr
         "Link Half and Block Import are present for Full Services or setup failed before. qed",
     );
 
+    if let Some(endpoint) = iroha_endpoint {
+        use sc_client_api::Backend;
+        use sp_core::{offchain::OffchainStorage, Encode};
+        use substrate_iroha_bridge_runtime::iroha_bridge;
+        match builder.backend().offchain_storage() {
+            Some(mut storage) => storage.set(
+                sp_core::offchain::STORAGE_PREFIX,
+                iroha_bridge::IROHA_ENDPOINT_KEY,
+                &endpoint.into_bytes().encode(),
+            ),
+            None => log::warn!("Offchain storage is not available, ignoring --iroha-endpoint"),
+        }
+    }
+
     let service = builder
         .with_finality_proof_provider(|client, backend| {
             // GenesisAuthoritySetProvider is implemented for StorageAndProofProvider
@@ -15,19 +15,22 @@ pub mod mock;
 mod utils;
 
 use alloc::collections::btree_set::BTreeSet;
+use alloc::string::String;
 use core::{line, stringify};
 use frame_support::dispatch::Weight;
+use frame_support::storage::IterableStorageMap;
 use frame_support::{
-    debug, decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, traits::Get,
+    debug, decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    traits::Get,
 };
 use frame_system::offchain::SignMessage;
 use frame_system::{
     self as system, ensure_root, ensure_signed,
     offchain::{AppCrypto, CreateSignedTransaction, SendSignedTransaction, Signer},
 };
-use iroha_client_no_std::account;
 use iroha_client_no_std::account::isi::AccountInstruction;
 use iroha_client_no_std::account::query::GetAccount;
+use iroha_client_no_std::asset::isi::AssetInstruction;
 use iroha_client_no_std::block::{Message as BlockMessage, ValidBlock};
 use iroha_client_no_std::bridge;
 use iroha_client_no_std::bridge::{BridgeDefinitionId, ExternalTransaction};
@@ -37,7 +40,7 @@ use iroha_client_no_std::prelude as iroha;
 use iroha_client_no_std::tx::RequestedTransaction;
 use parity_scale_codec::{Decode, Encode};
 use sp_core::crypto::KeyTypeId;
-use sp_runtime::traits::Hash;
+use sp_runtime::traits::{Hash, UniqueSaturatedInto, Zero};
 use sp_runtime::{
     offchain as rt_offchain, offchain::storage::StorageValueRef,
     transaction_validity::TransactionPriority,
@@ -45,15 +48,31 @@ use sp_runtime::{
 use sp_std::convert::TryFrom;
 use sp_std::prelude::*;
 use sp_std::str;
-use treasury::AssetKind;
+use treasury::{AssetId, AssetMode};
+
+pub use utils::is_valid_iroha_endpoint;
 
 pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"demo");
 pub const KEY_TYPE_2: KeyTypeId = KeyTypeId(*b"dem0");
 pub const NUM_VEC_LEN: usize = 10;
 
-pub const INSTRUCTION_ENDPOINT: &str = "http://127.0.0.1:7878/instruction";
-pub const BLOCK_ENDPOINT: &str = "http://127.0.0.1:7878/block";
-pub const QUERY_ENDPOINT: &str = "http://127.0.0.1:7878/query";
+pub const INSTRUCTION_PATH: &str = "/instruction";
+pub const BLOCK_PATH: &str = "/block";
+pub const QUERY_PATH: &str = "/query";
+
+/// Offchain local storage key of the Iroha peer URL. When set (by the node's `--iroha-endpoint`
+/// flag or the `offchain_localStorageSet` RPC) it takes precedence over `IrohaEndpoint`.
+pub const IROHA_ENDPOINT_KEY: &[u8] = b"iroha-bridge-ocw::iroha-endpoint";
+
+/// Maximum number of Iroha instructions a split outgoing transfer is sent with.
+pub const MAX_OUTGOING_TRANSFER_PARTS: u128 = 16;
+
+/// Time to live of the Iroha transactions sent by the bridge. Every authority signs the same
+/// transaction with a creation time taken from the chain, so it has to outlive the slowest one.
+pub const IROHA_TX_TIME_TO_LIVE_MS: u64 = 24 * 60 * 60 * 1000;
+
+/// Domain tag of the message an Iroha key signs to link its account, see `link_message`.
+pub const LINK_MESSAGE_DOMAIN: &[u8] = b"iroha-bridge:link";
 
 pub mod crypto {
     use crate::KEY_TYPE;
@@ -94,7 +113,9 @@ pub mod crypto_ed {
 }
 
 /// This is the pallet's configuration trait
-pub trait Trait: system::Trait + treasury::Trait + CreateSignedTransaction<Call<Self>> {
+pub trait Trait:
+    system::Trait + treasury::Trait + pallet_timestamp::Trait + CreateSignedTransaction<Call<Self>>
+{
     /// The identifier type for an offchain worker.
     type AuthorityId: AppCrypto<Self::Public, Self::Signature>;
     /// The identifier type for an offchain worker with Ed25519 keys.
@@ -105,29 +126,251 @@ pub trait Trait: system::Trait + treasury::Trait + CreateSignedTransaction<Call<
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
     /// The type to sign and send transactions.
     type UnsignedPriority: Get<TransactionPriority>;
+    /// Number of blocks an outgoing transfer has to be approved in before it is retried.
+    type OutgoingTransferTimeout: Get<Self::BlockNumber>;
+    /// Number of timeouts after which a not approved outgoing transfer is cancelled and its
+    /// funds are unlocked. Approved transfers may already be executed on Iroha, so they are
+    /// never cancelled by a timeout.
+    type MaxOutgoingTransferRetries: Get<u32>;
+    /// Number of blocks an incoming transfer has to reach the threshold in after its first
+    /// attestation. The attestations then count as votes to send the transfer back.
+    type IncomingAttestationTimeout: Get<Self::BlockNumber>;
+    /// Handling of outgoing transfers exceeding the `u32` quantity of an Iroha instruction.
+    type OversizedTransferPolicy: Get<OversizedTransferPolicy>;
+    /// SS58 address type of this chain. Memos naming the credited account must use it.
+    type Ss58Prefix: Get<u8>;
+}
+
+/// Handling of outgoing transfers exceeding the `u32` quantity of an Iroha instruction.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum OversizedTransferPolicy {
+    /// Reject the request in `request_transfer`.
+    Reject,
+    /// Send the transfer as up to `MAX_OUTGOING_TRANSFER_PARTS` instructions in one Iroha
+    /// transaction.
+    Split,
+}
+
+/// Identifier of an outgoing transfer request.
+pub type RequestId = treasury::RequestId;
+
+/// Sender-chosen nonce making an outgoing transfer request idempotent.
+pub type Nonce = u64;
+
+/// Stage of a pending outgoing transfer. Once burned or expired the request is removed.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum OutgoingTransferStatus {
+    /// The funds are locked, waiting for authorities' approvals.
+    Requested,
+    /// Approved by `Threshold` authorities, waiting for its Iroha transaction to be committed.
+    Approved,
+}
+
+/// Outgoing transfer from Substrate to Iroha.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct OutgoingTransfer<AccountId, BlockNumber> {
+    pub sender: AccountId,
+    pub receiver: iroha::AccountId,
+    pub asset_id: AssetId,
+    pub amount: u128,
+    pub nonce: Nonce,
+    pub status: OutgoingTransferStatus,
+    /// Block the transfer was requested at.
+    pub created_at: BlockNumber,
+    /// Index of the requesting extrinsic within `created_at` block.
+    pub extrinsic_index: u32,
+    /// Number of timeouts the transfer has been retried after.
+    pub retries: u32,
+    /// Block at which the request is retried or expires unless approved.
+    pub deadline: BlockNumber,
+    /// Bridge account transaction registering the approved transfer on Iroha.
+    pub iroha_tx: Option<IrohaTxId>,
 }
 
-/// The type of requests we can send to the offchain worker
-#[cfg_attr(feature = "std", derive(PartialEq, Eq, Debug))]
-#[derive(Encode, Decode)]
-pub enum OffchainRequest<T: system::Trait + treasury::Trait> {
-    /// Outgoing transfer from Substrate to Iroha request.
-    OutgoingTransfer(
-        T::AccountId,
-        iroha::AccountId,
-        treasury::AssetKind,
-        u128,
-        u8,
-    ),
+/// Identifier of a bridge account transaction queued for Iroha.
+pub type IrohaTxId = u64;
+
+/// What a queued Iroha transaction does, deciding what happens on chain once it is committed.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub enum IrohaTxPurpose<Hash> {
+    /// Registers an approved outgoing transfer with the bridge definition. Its funds are burned
+    /// once the transaction is committed.
+    OutgoingTransfer(RequestId),
+    /// Registers a credited incoming transfer with the bridge definition (Iroha transaction hash,
+    /// instruction index).
+    IncomingTransfer(Hash, u32),
+    /// Sends an incoming transfer that can't be credited back to its Iroha sender (Iroha
+    /// transaction hash, instruction index).
+    IncomingTransferRefund(Hash, u32),
+    /// Applies a change of the bridge account's signatories.
+    SignatoryUpdate(SignatoryUpdateId),
+    /// Sets the number of signatures the bridge account's transactions need to `Threshold`.
+    QuorumUpdate(u32),
+}
+
+/// Transaction of the bridge account the authorities sign and send to Iroha until it shows up
+/// in a committed Iroha block.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone)]
+pub struct PendingIrohaTx<Hash> {
+    pub purpose: IrohaTxPurpose<Hash>,
+    /// Unsigned transaction built on chain, so that every authority signs the same payload.
+    pub tx: RequestedTransaction,
+    /// Hash of the transaction payload, identifying the transaction in Iroha blocks.
+    pub hash: Hash,
+    /// Whether an authority got the transaction accepted by an Iroha peer.
+    pub sent: bool,
+}
+
+/// Transfer into the bridge account found in an Iroha transaction.
+#[cfg_attr(feature = "std", derive(Debug))]
+pub struct BridgeTransfer {
+    /// Index of the transfer within the transaction. Top-level instructions keep their own
+    /// index, transfers nested in composite instructions are numbered after them.
+    pub isi_index: u32,
+    pub from: iroha::AccountId,
+    pub asset: iroha::Asset,
+    /// Whether the transfer is part of an `If` instruction, so it is unknown from the block
+    /// whether Iroha executed it.
+    pub conditional: bool,
+}
+
+/// Identifier of a queued change of the Iroha bridge account's signatories.
+pub type SignatoryUpdateId = u64;
+
+/// Change of the Iroha bridge account's signatories following the authority set.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq)]
+pub enum SignatoryUpdate {
+    Add(iroha_crypto::PublicKey),
+    Remove(iroha_crypto::PublicKey),
+}
+
+/// Details of an incoming transfer as attested by an authority.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, PartialEq, Eq)]
+pub struct IncomingTransfer<AccountId> {
+    pub sender: iroha::AccountId,
+    pub receiver: AccountId,
+    pub receiver_source: ReceiverSource,
+    pub asset_id: AssetId,
+    /// Transferred quantity in Iroha decimals.
+    pub quantity: u128,
+}
+
+/// How the account credited with an incoming transfer was found.
+#[cfg_attr(feature = "std", derive(Debug))]
+#[derive(Encode, Decode, Clone, Copy, PartialEq, Eq)]
+pub enum ReceiverSource {
+    /// The SS58 address in the memo of the Iroha transaction.
+    Memo,
+    /// The account linked to the sender with `link_iroha_account`.
+    Link,
+    /// The account derived from the first signatory of the sender. Only this default mapping
+    /// is cached in `Accounts`.
+    Signatory,
 }
 
 decl_storage! {
     trait Store for Module<T: Trait> as Example {
-        /// Requests for off-chain workers made within this block execution
-        OcRequests get(fn oc_requests): Vec<OffchainRequest<T>>;
-        Authorities get(fn authorities) config(): Vec<T::AccountId>;
+        NextRequestId get(fn next_request_id): RequestId;
+        /// Outgoing transfers processed by the offchain workers until burned or expired.
+        PendingOutgoing get(fn pending_outgoing): map hasher(twox_64_concat) RequestId => Option<OutgoingTransfer<T::AccountId, T::BlockNumber>>;
+        /// Authorities that approved a requested outgoing transfer.
+        OutgoingApprovals get(fn outgoing_approvals): map hasher(twox_64_concat) RequestId => Vec<T::AccountId>;
+        NextIrohaTxId get(fn next_iroha_tx_id): IrohaTxId;
+        /// Bridge account transactions waiting to be committed on Iroha.
+        PendingIrohaTxs get(fn pending_iroha_tx): map hasher(twox_64_concat) IrohaTxId => Option<PendingIrohaTx<T::Hash>>;
+        /// Authorities that found a queued transaction in a committed Iroha block.
+        IrohaTxCommitVotes get(fn iroha_tx_commit_votes): map hasher(twox_64_concat) IrohaTxId => Vec<T::AccountId>;
+        /// Authorities that found a sent transaction expired on Iroha without being committed.
+        IrohaTxExpiryVotes get(fn iroha_tx_expiry_votes): map hasher(twox_64_concat) IrohaTxId => Vec<T::AccountId>;
+        /// Authorities that voted to refund a failed outgoing transfer.
+        OutgoingRefundVotes get(fn outgoing_refund_votes): map hasher(twox_64_concat) RequestId => Vec<T::AccountId>;
+        /// Nonces of outgoing transfer requests already made by an account.
+        UsedNonces get(fn used_nonces): double_map hasher(blake2_128_concat) T::AccountId, hasher(twox_64_concat) Nonce => bool;
+        /// Outgoing transfers timing out at a given block.
+        OutgoingDeadlines: map hasher(twox_64_concat) T::BlockNumber => Vec<RequestId>;
+        /// Authorities and the keys they sign Iroha transactions with.
+        Authorities get(fn authority_key): map hasher(blake2_128_concat) T::AccountId => Option<iroha_crypto::PublicKey>;
+        /// Number of `Authorities`.
+        AuthorityCount get(fn authority_count): u32;
+        NextSignatoryUpdateId get(fn next_signatory_update_id): SignatoryUpdateId;
+        /// Changes of the authority set not yet committed on the Iroha bridge account, with the
+        /// transactions applying them.
+        PendingSignatoryUpdates get(fn pending_signatory_updates): map hasher(twox_64_concat) SignatoryUpdateId => Option<(SignatoryUpdate, IrohaTxId)>;
+        /// Multi-signature Iroha account the bridge holds the transferred assets in and sends
+        /// transactions as. Its signatories are the authorities' keys. Defaults to
+        /// `bridge@polkadot`.
+        BridgeAccount get(fn bridge_account): Option<iroha::AccountId>;
+        /// Iroha bridge definition of this deployment. Defaults to the bridge account's domain.
+        BridgeDefinition get(fn bridge_definition): Option<BridgeDefinitionId>;
         Accounts: map hasher(twox_64_concat) iroha::AccountId => T::AccountId;
+        /// Substrate accounts explicitly linked to Iroha accounts, with the Iroha key that proved
+        /// the ownership. The authorities checked that the key was a signatory of the account.
+        LinkedAccounts get(fn linked_account): map hasher(blake2_128_concat) iroha::AccountId => Option<(T::AccountId, iroha_crypto::PublicKey)>;
+        /// Links waiting for the authorities to check that the proving key is a signatory of the
+        /// Iroha account, by the Iroha account and the account to link.
+        PendingLinks get(fn pending_link): map hasher(blake2_128_concat) (iroha::AccountId, T::AccountId) => Option<iroha_crypto::PublicKey>;
+        /// Authorities that found the key of a pending link to be a signatory of the Iroha
+        /// account, or not.
+        LinkCheckVotes: map hasher(blake2_128_concat) (iroha::AccountId, T::AccountId, iroha_crypto::PublicKey, bool) => Vec<T::AccountId>;
+        /// Iroha peers signing blocks from the height `IrohaPeersActiveFrom` on.
         IrohaPeers get(fn iroha_peers) config(): Vec<iroha_crypto::PublicKey>;
+        /// Iroha block height from which `IrohaPeers` is the valid peer set.
+        IrohaPeersActiveFrom get(fn iroha_peers_active_from): u64;
+        /// Superseded Iroha peer sets with the heights they became valid at, in ascending order.
+        PreviousIrohaPeers get(fn previous_iroha_peers): Vec<(u64, Vec<iroha_crypto::PublicKey>)>;
+        /// Number of distinct authorities that must agree before the bridge acts.
+        Threshold get(fn threshold) config(): u32;
+        /// Threshold set by `root` with the transaction updating the Iroha bridge account's quorum
+        /// to it. It takes effect once that transaction and the signatories added before it are
+        /// committed on Iroha.
+        PendingThreshold get(fn pending_threshold): Option<(u32, IrohaTxId)>;
+        /// Authorities' attestations of Iroha transfers that are not minted yet.
+        IncomingAttestations get(fn incoming_attestations): map hasher(blake2_128_concat) (T::Hash, u32) => Vec<(T::AccountId, IncomingTransfer<T::AccountId>)>;
+        /// Incoming transfers whose attestations turn into refund votes at a given block unless
+        /// the threshold is reached before.
+        IncomingDeadlines: map hasher(twox_64_concat) T::BlockNumber => Vec<(T::Hash, u32)>;
+        /// Authorities that voted to send an incoming transfer back to its Iroha sender, by the
+        /// transfer's Iroha transaction hash, instruction index, sender, asset and quantity.
+        IncomingRefundVotes get(fn incoming_refund_votes): map hasher(blake2_128_concat) (T::Hash, u32, iroha::AccountId, iroha::AssetDefinitionId, u32) => Vec<T::AccountId>;
+        /// Authorities that reported a transfer under an `If` instruction, by the transfer's Iroha
+        /// transaction hash, instruction index, sender, asset and quantity.
+        ConditionalTransferVotes: map hasher(blake2_128_concat) (T::Hash, u32, iroha::AccountId, iroha::AssetDefinitionId, u32) => Vec<T::AccountId>;
+        /// Transfers under an `If` instruction waiting for `root` to tell whether Iroha executed
+        /// them, with their sender, asset and quantity.
+        ConditionalIncomingTransfers get(fn conditional_transfer): map hasher(blake2_128_concat) (T::Hash, u32) => Option<(iroha::AccountId, iroha::AssetDefinitionId, u32)>;
+        /// Iroha transaction hashes and instruction indices already minted on this chain.
+        ProcessedIrohaTxs get(fn processed_iroha_txs): map hasher(blake2_128_concat) (T::Hash, u32) => bool;
+        /// Default URL of the Iroha peer, e.g. `http://127.0.0.1:7878`.
+        IrohaEndpoint get(fn iroha_endpoint) config(): Vec<u8>;
+        /// Number of distinct peer signatures an Iroha block needs. Defaults to `n - f` of
+        /// `IrohaPeers` when unset.
+        IrohaPeersThreshold get(fn iroha_peers_threshold): Option<u32>;
+    }
+    add_extra_genesis {
+        /// Authorities with their Iroha keys, already signatories of the Iroha bridge account.
+        config(authorities): Vec<(T::AccountId, iroha_crypto::PublicKey)>;
+        config(bridge_account): Option<iroha::AccountId>;
+        config(bridge_definition): Option<BridgeDefinitionId>;
+        build(|config: &GenesisConfig<T>| {
+            if let Some(account) = &config.bridge_account {
+                BridgeAccount::put(account);
+            }
+            if let Some(definition) = &config.bridge_definition {
+                BridgeDefinition::put(definition);
+            }
+            for (who, key) in &config.authorities {
+                assert!(!<Authorities<T>>::contains_key(who), "authorities are unique");
+                <Authorities<T>>::insert(who, key);
+            }
+            AuthorityCount::put(config.authorities.len() as u32);
+        });
     }
 }
 
@@ -136,9 +379,75 @@ decl_event!(
     pub enum Event<T>
     where
         AccId = <T as system::Trait>::AccountId,
+        Hash = <T as system::Trait>::Hash,
     {
-        IncomingTransfer(iroha::AccountId, AccId, AssetKind, u128),
-        OutgoingTransfer(AccId, iroha::AccountId, AssetKind, u128),
+        IncomingTransfer(iroha::AccountId, AccId, AssetId, u128),
+        /// An authority attested an incoming transfer (Iroha tx hash, instruction index, authority).
+        IncomingTransferAttested(Hash, u32, AccId),
+        /// An authority attested details different from an earlier attestation of the same
+        /// Iroha instruction.
+        IncomingTransferDisputed(Hash, u32, AccId),
+        /// The attested Iroha quantity can't be represented on this chain and is not minted
+        /// (Iroha tx hash, instruction index).
+        IncomingTransferRejected(Hash, u32),
+        /// The incoming transfer (Iroha transaction hash, instruction index) is sent back to its
+        /// Iroha sender.
+        IncomingTransferRefunded(Hash, u32),
+        /// The incoming transfer (Iroha transaction hash, instruction index) is part of an `If`
+        /// instruction and waits for `root` to tell whether Iroha executed it.
+        ConditionalIncomingTransfer(Hash, u32),
+        /// `root` dropped a conditional incoming transfer Iroha didn't execute.
+        ConditionalIncomingTransferDropped(Hash, u32),
+        /// The attestations of an incoming transfer did not reach the threshold in time and count
+        /// as votes to send it back (Iroha tx hash, instruction index).
+        IncomingTransferExpired(Hash, u32),
+        OutgoingTransferRequested(RequestId, AccId, iroha::AccountId, AssetId, u128),
+        OutgoingTransferApproved(RequestId),
+        /// The requested amount can't be represented in the asset's Iroha decimals and nothing is
+        /// locked (sender, asset, amount).
+        OutgoingTransferRejected(AccId, AssetId, u128),
+        /// The transfer timed out and is processed again (request id, retry number).
+        OutgoingTransferRetried(RequestId, u32),
+        /// The transfer was committed on Iroha and the locked funds are burned.
+        OutgoingTransfer(RequestId, AccId, iroha::AccountId, AssetId, u128),
+        /// The transfer was not approved in time and the locked funds are unlocked.
+        OutgoingTransferExpired(RequestId),
+        /// The transfer's Iroha transaction expired unsent and the locked funds are unlocked
+        /// (request id, failure reason).
+        OutgoingTransferFailed(RequestId, Vec<u8>),
+        IrohaEndpointChanged(Vec<u8>),
+        BridgeAccountChanged(iroha::AccountId),
+        BridgeDefinitionChanged(BridgeDefinitionId),
+        /// A key of the Iroha account signed the link, which waits for the authorities to check
+        /// that the key is a signatory of the account.
+        IrohaAccountLinkRequested(AccId, iroha::AccountId),
+        IrohaAccountLinked(AccId, iroha::AccountId),
+        /// The key that signed the link is not a signatory of the Iroha account.
+        IrohaAccountLinkRejected(AccId, iroha::AccountId),
+        IrohaAccountUnlinked(AccId, iroha::AccountId),
+        IrohaPeersThresholdChanged(Option<u32>),
+        IrohaPeerAdded(iroha_crypto::PublicKey),
+        /// The peer no longer signs Iroha blocks from the given height on.
+        IrohaPeerRemoved(iroha_crypto::PublicKey, u64),
+        /// The Iroha peer set was replaced from the given height on.
+        IrohaPeersSet(u64),
+        AuthorityAdded(AccId),
+        AuthorityRemoved(AccId),
+        /// The Iroha key of an authority was replaced.
+        AuthorityKeyChanged(AccId),
+        /// A new threshold waits for the Iroha bridge account's quorum to be updated.
+        ThresholdChangeQueued(u32),
+        ThresholdChanged(u32),
+        /// A bridge account transaction is waiting to be committed on Iroha.
+        IrohaTxQueued(IrohaTxId),
+        /// `Threshold` authorities found a queued transaction in a committed Iroha block.
+        IrohaTxCommitted(IrohaTxId),
+        /// A queued transaction expired on Iroha and was created anew.
+        IrohaTxRequeued(IrohaTxId),
+        /// A change of the Iroha bridge account's signatories is waiting for the offchain workers.
+        SignatoryUpdateQueued(SignatoryUpdateId),
+        /// A change of the Iroha bridge account's signatories was committed on Iroha.
+        SignatoryUpdateApplied(SignatoryUpdateId),
     }
 );
 
@@ -150,8 +459,42 @@ decl_error! {
         AccountNotFound,
         InvalidBalanceType,
         InvalidBlockSignature,
+        NotEnoughBlockSignatures,
+        IrohaBlockGap,
+        IrohaForkDetected,
         SubmitInstructionsFailed,
         SendSignedTransactionError,
+        InvalidIrohaEndpoint,
+        IrohaTxAlreadyProcessed,
+        NotAnAuthority,
+        AlreadyAttested,
+        UnknownRequest,
+        InvalidRequestStatus,
+        AlreadyApproved,
+        AlreadyVotedForRefund,
+        UnknownAsset,
+        DuplicateNonce,
+        AmountTooLarge,
+        AlreadyAnAuthority,
+        DuplicateAuthority,
+        DuplicateAuthorityKey,
+        InvalidThreshold,
+        UnknownIrohaPeer,
+        InvalidLinkSignature,
+        IrohaAccountAlreadyLinked,
+        IrohaAccountNotLinked,
+        UnknownLinkRequest,
+        DuplicateIrohaPeer,
+        EmptyIrohaPeerSet,
+        InvalidActivationHeight,
+        InvalidIrohaPeersThreshold,
+        UnknownIrohaTx,
+        AlreadyReported,
+        IrohaTxExpired,
+        IrohaTxNotExpired,
+        OutgoingTransferAlreadySent,
+        UnknownConditionalTransfer,
+        BridgeAccountInUse,
         Other,
     }
 }
@@ -160,70 +503,616 @@ decl_module! {
     pub struct Module<T: Trait> for enum Call where origin: T::Origin {
         fn deposit_event() = default;
 
-        /// Clean the state on initialisation of a block
-        fn on_initialize(_now: T::BlockNumber) -> Weight {
+        /// Retry or cancel outgoing transfers that timed out
+        fn on_initialize(now: T::BlockNumber) -> Weight {
             debug::debug!("called on_initialize");
-            <Self as Store>::OcRequests::kill();
+            for request_id in <OutgoingDeadlines<T>>::take(now) {
+                let mut transfer = match <PendingOutgoing<T>>::get(request_id) {
+                    Some(transfer) => transfer,
+                    None => continue,
+                };
+                if transfer.status != OutgoingTransferStatus::Requested {
+                    continue;
+                }
+                if transfer.retries < T::MaxOutgoingTransferRetries::get() {
+                    transfer.retries += 1;
+                    transfer.deadline = now + T::OutgoingTransferTimeout::get();
+                    // let the authorities approve it anew
+                    <OutgoingApprovals<T>>::remove(request_id);
+                    <OutgoingDeadlines<T>>::mutate(transfer.deadline, |v| v.push(request_id));
+                    Self::deposit_event(RawEvent::OutgoingTransferRetried(request_id, transfer.retries));
+                    <PendingOutgoing<T>>::insert(request_id, transfer);
+                    continue;
+                }
+                <PendingOutgoing<T>>::remove(request_id);
+                <OutgoingApprovals<T>>::remove(request_id);
+                <OutgoingRefundVotes<T>>::remove(request_id);
+                if let Err(e) = <treasury::Module<T>>::unlock(transfer.sender, transfer.asset_id, transfer.amount, Some(request_id)) {
+                    debug::error!("Failed to unlock funds of expired request {}: {:?}", request_id, e);
+                }
+                Self::deposit_event(RawEvent::OutgoingTransferExpired(request_id));
+            }
+            for (iroha_tx_hash, isi_index) in <IncomingDeadlines<T>>::take(now) {
+                let key = (iroha_tx_hash, isi_index);
+                if !<IncomingAttestations<T>>::contains_key(key) {
+                    continue;
+                }
+                Self::deposit_event(RawEvent::IncomingTransferExpired(iroha_tx_hash, isi_index));
+                // the offchain workers don't look at the Iroha block again, so the transfer is sent
+                // back once `Threshold` authorities attested the same sender, asset and quantity
+                for (author, transfer) in <IncomingAttestations<T>>::take(key) {
+                    let refund = <treasury::Module<T>>::definition_id(transfer.asset_id)
+                        .map_err(Into::into)
+                        .and_then(|definition_id| {
+                            let quantity = transfer.quantity as u32;
+                            Self::vote_incoming_transfer_refund(author, iroha_tx_hash, isi_index, transfer.sender, definition_id, quantity)
+                        });
+                    if let Err(e) = refund {
+                        debug::warn!("Failed to refund expired incoming transfer {:?} #{}: {:?}", iroha_tx_hash, isi_index, e);
+                    }
+                }
+            }
             0
         }
 
+        /// Records that an Iroha peer accepted a queued transaction sent by an authority.
+        #[weight = 0]
+        pub fn iroha_tx_sent(origin, iroha_tx_id: IrohaTxId) -> DispatchResult {
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let mut pending = Self::pending_iroha_tx(iroha_tx_id).ok_or(<Error<T>>::UnknownIrohaTx)?;
+            ensure!(!Self::is_iroha_tx_expired(&pending.tx, Self::now_millis()), <Error<T>>::IrohaTxExpired);
+            if !pending.sent {
+                pending.sent = true;
+                <PendingIrohaTxs<T>>::insert(iroha_tx_id, pending);
+            }
+            Ok(())
+        }
+
+        /// Reports a queued transaction found in a committed Iroha block. With `Threshold` reports
+        /// the transaction is done, e.g. the funds of an outgoing transfer are burned.
+        #[weight = 0]
+        pub fn iroha_tx_committed(origin, iroha_tx_id: IrohaTxId) -> DispatchResult {
+            debug::debug!("called iroha_tx_committed");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let pending = Self::pending_iroha_tx(iroha_tx_id).ok_or(<Error<T>>::UnknownIrohaTx)?;
+            let mut votes = <IrohaTxCommitVotes<T>>::get(iroha_tx_id);
+            ensure!(!votes.contains(&author), <Error<T>>::AlreadyReported);
+
+            votes.push(author);
+            if !Self::is_threshold_reached(votes.len()) {
+                <IrohaTxCommitVotes<T>>::insert(iroha_tx_id, votes);
+                return Ok(());
+            }
+            match pending.purpose {
+                IrohaTxPurpose::OutgoingTransfer(request_id) => Self::finalize_outgoing_transfer(request_id)?,
+                IrohaTxPurpose::SignatoryUpdate(update_id) => {
+                    PendingSignatoryUpdates::remove(update_id);
+                    Self::deposit_event(RawEvent::SignatoryUpdateApplied(update_id));
+                }
+                IrohaTxPurpose::IncomingTransfer(..)
+                | IrohaTxPurpose::IncomingTransferRefund(..)
+                | IrohaTxPurpose::QuorumUpdate(_) => (),
+            }
+            <PendingIrohaTxs<T>>::remove(iroha_tx_id);
+            <IrohaTxCommitVotes<T>>::remove(iroha_tx_id);
+            <IrohaTxExpiryVotes<T>>::remove(iroha_tx_id);
+            Self::deposit_event(RawEvent::IrohaTxCommitted(iroha_tx_id));
+            Self::apply_pending_threshold();
+            Ok(())
+        }
+
+        /// Reports a sent transaction that expired on Iroha without being committed. With
+        /// `Threshold` reports the transaction is created anew and sent again.
         #[weight = 0]
-        pub fn outgoing_transfer(origin, sender: T::AccountId, receiver: iroha::AccountId, asset_kind: AssetKind, amount: u128, nonce: u8) -> DispatchResult {
-            debug::debug!("called outgoing_transfer");
+        pub fn iroha_tx_expired(origin, iroha_tx_id: IrohaTxId) -> DispatchResult {
+            debug::debug!("called iroha_tx_expired");
             let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let pending = Self::pending_iroha_tx(iroha_tx_id).ok_or(<Error<T>>::UnknownIrohaTx)?;
+            ensure!(Self::is_iroha_tx_expired(&pending.tx, Self::now_millis()), <Error<T>>::IrohaTxNotExpired);
+            let mut votes = <IrohaTxExpiryVotes<T>>::get(iroha_tx_id);
+            ensure!(!votes.contains(&author), <Error<T>>::AlreadyReported);
 
-            if Self::is_authority(&author) {
-                <treasury::Module<T>>::burn(sender.clone(), asset_kind, amount)?;
-                debug::info!("Finalized outgoing transfer request {} {:?} from {:?} to {}", amount, asset_kind, sender, receiver);
-                Self::deposit_event(RawEvent::OutgoingTransfer(sender, receiver, asset_kind, amount));
+            votes.push(author);
+            if !Self::is_threshold_reached(votes.len()) {
+                <IrohaTxExpiryVotes<T>>::insert(iroha_tx_id, votes);
+                return Ok(());
             }
+            let tx = Self::bridge_tx(pending.tx.payload.instructions, Self::now_millis());
+            let hash = T::Hashing::hash(&tx.payload.encode());
+            <PendingIrohaTxs<T>>::insert(iroha_tx_id, PendingIrohaTx { purpose: pending.purpose, tx, hash, sent: false });
+            <IrohaTxCommitVotes<T>>::remove(iroha_tx_id);
+            <IrohaTxExpiryVotes<T>>::remove(iroha_tx_id);
+            Self::deposit_event(RawEvent::IrohaTxRequeued(iroha_tx_id));
+            Ok(())
+        }
+
+        /// Approves a requested outgoing transfer. With `Threshold` approvals its Iroha transaction
+        /// is queued.
+        #[weight = 0]
+        pub fn approve_outgoing_transfer(origin, request_id: RequestId) -> DispatchResult {
+            debug::debug!("called approve_outgoing_transfer");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let mut transfer = <PendingOutgoing<T>>::get(request_id).ok_or(<Error<T>>::UnknownRequest)?;
+            ensure!(transfer.status == OutgoingTransferStatus::Requested, <Error<T>>::InvalidRequestStatus);
+            let mut approvals = <OutgoingApprovals<T>>::get(request_id);
+            ensure!(!approvals.contains(&author), <Error<T>>::AlreadyApproved);
+
+            approvals.push(author);
+            if !Self::is_threshold_reached(approvals.len()) {
+                <OutgoingApprovals<T>>::insert(request_id, approvals);
+                return Ok(());
+            }
+            let instructions = Self::outgoing_instructions(request_id, &transfer)?;
+            transfer.status = OutgoingTransferStatus::Approved;
+            transfer.iroha_tx = Some(Self::queue_iroha_tx(IrohaTxPurpose::OutgoingTransfer(request_id), instructions));
+            <PendingOutgoing<T>>::insert(request_id, transfer);
+            <OutgoingApprovals<T>>::remove(request_id);
+            Self::deposit_event(RawEvent::OutgoingTransferApproved(request_id));
+            Ok(())
+        }
+
+        /// Votes to refund an approved outgoing transfer whose Iroha transaction expired without
+        /// any authority getting it accepted. With `Threshold` votes the locked funds are unlocked.
+        #[weight = 0]
+        pub fn refund_outgoing_transfer(origin, request_id: RequestId, reason: Vec<u8>) -> DispatchResult {
+            debug::debug!("called refund_outgoing_transfer");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let transfer = <PendingOutgoing<T>>::get(request_id).ok_or(<Error<T>>::UnknownRequest)?;
+            ensure!(transfer.status == OutgoingTransferStatus::Approved, <Error<T>>::InvalidRequestStatus);
+            let iroha_tx_id = transfer.iroha_tx.ok_or(<Error<T>>::InvalidRequestStatus)?;
+            let pending = Self::pending_iroha_tx(iroha_tx_id).ok_or(<Error<T>>::UnknownIrohaTx)?;
+            ensure!(!pending.sent, <Error<T>>::OutgoingTransferAlreadySent);
+            ensure!(Self::is_iroha_tx_expired(&pending.tx, Self::now_millis()), <Error<T>>::IrohaTxNotExpired);
+            let mut votes = <OutgoingRefundVotes<T>>::get(request_id);
+            ensure!(!votes.contains(&author), <Error<T>>::AlreadyVotedForRefund);
 
+            votes.push(author);
+            if !Self::is_threshold_reached(votes.len()) {
+                <OutgoingRefundVotes<T>>::insert(request_id, votes);
+                return Ok(());
+            }
+            <treasury::Module<T>>::unlock(transfer.sender, transfer.asset_id, transfer.amount, Some(request_id))?;
+            <PendingIrohaTxs<T>>::remove(iroha_tx_id);
+            <IrohaTxCommitVotes<T>>::remove(iroha_tx_id);
+            <IrohaTxExpiryVotes<T>>::remove(iroha_tx_id);
+            <PendingOutgoing<T>>::remove(request_id);
+            <OutgoingApprovals<T>>::remove(request_id);
+            <OutgoingRefundVotes<T>>::remove(request_id);
+            Self::deposit_event(RawEvent::OutgoingTransferFailed(request_id, reason));
             Ok(())
         }
 
         #[weight = 0]
-        pub fn request_transfer(origin, receiver: iroha::AccountId, asset_kind: AssetKind, amount: u128, nonce: u8) -> DispatchResult {
+        pub fn request_transfer(origin, receiver: iroha::AccountId, asset_id: AssetId, amount: u128, nonce: Nonce) -> DispatchResult {
             debug::debug!("called request_transfer");
             let from = ensure_signed(origin)?;
+            ensure!(!<UsedNonces<T>>::get(&from, nonce), <Error<T>>::DuplicateNonce);
+            // reject amounts that can't be represented in Iroha decimals without failing, so
+            // that the event is kept
+            let quantity = match <treasury::Module<T>>::to_iroha_quantity(asset_id, amount) {
+                Err(treasury::Error::<T>::PrecisionLoss) => {
+                    Self::deposit_event(RawEvent::OutgoingTransferRejected(from, asset_id, amount));
+                    return Ok(());
+                }
+                quantity => quantity?,
+            };
+            let max_quantity = match T::OversizedTransferPolicy::get() {
+                OversizedTransferPolicy::Reject => u128::from(u32::MAX),
+                OversizedTransferPolicy::Split => u128::from(u32::MAX) * MAX_OUTGOING_TRANSFER_PARTS,
+            };
+            ensure!(quantity <= max_quantity, <Error<T>>::AmountTooLarge);
+
+            let request_id = Self::next_request_id();
+            <treasury::Module<T>>::lock(from.clone(), asset_id, amount, Some(request_id))?;
+            <UsedNonces<T>>::insert(&from, nonce, true);
+            NextRequestId::put(request_id + 1);
+            let now = <system::Module<T>>::block_number();
+            let extrinsic_index = <system::Module<T>>::extrinsic_index().unwrap_or_default();
+            let deadline = now + T::OutgoingTransferTimeout::get();
+            <PendingOutgoing<T>>::insert(request_id, OutgoingTransfer {
+                sender: from.clone(),
+                receiver: receiver.clone(),
+                asset_id,
+                amount,
+                nonce,
+                status: OutgoingTransferStatus::Requested,
+                created_at: now,
+                extrinsic_index,
+                retries: 0,
+                deadline,
+                iroha_tx: None,
+            });
+            <OutgoingDeadlines<T>>::mutate(deadline, |v| v.push(request_id));
+            Self::deposit_event(RawEvent::OutgoingTransferRequested(request_id, from, receiver, asset_id, amount));
+            Ok(())
+        }
+
+        /// Attests that the `isi_index`-th instruction of the Iroha transaction `iroha_tx_hash`
+        /// transferred `quantity` of `asset_id` from `sender` to the bridge for `receiver`.
+        /// The transfer is minted once `Threshold` authorities attested identical details;
+        /// each instruction can be minted only once.
+        #[weight = 0]
+        pub fn incoming_transfer(origin, iroha_tx_hash: T::Hash, isi_index: u32, sender: iroha::AccountId, receiver: T::AccountId, receiver_source: ReceiverSource, asset_id: AssetId, quantity: u128) -> DispatchResult {
+            debug::debug!("called incoming_transfer");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let key = (iroha_tx_hash, isi_index);
+            ensure!(!<ProcessedIrohaTxs<T>>::get(key), <Error<T>>::IrohaTxAlreadyProcessed);
+            // Iroha quantities are `u32`
+            ensure!(quantity <= u128::from(u32::MAX), <Error<T>>::AmountTooLarge);
+
+            let mut attestations = <IncomingAttestations<T>>::get(key);
+            ensure!(!attestations.iter().any(|(who, _)| who == &author), <Error<T>>::AlreadyAttested);
+            let transfer = IncomingTransfer { sender, receiver, receiver_source, asset_id, quantity };
+            if attestations.iter().any(|(_, t)| t != &transfer) {
+                debug::warn!("{:?} attested a conflicting transfer for {:?} #{}", author, iroha_tx_hash, isi_index);
+                Self::deposit_event(RawEvent::IncomingTransferDisputed(iroha_tx_hash, isi_index, author.clone()));
+            }
+            if attestations.is_empty() {
+                let deadline = <system::Module<T>>::block_number() + T::IncomingAttestationTimeout::get();
+                <IncomingDeadlines<T>>::mutate(deadline, |v| v.push(key));
+            }
+            attestations.push((author.clone(), transfer.clone()));
+            Self::deposit_event(RawEvent::IncomingTransferAttested(iroha_tx_hash, isi_index, author));
 
-            <treasury::Module<T>>::lock(from.clone(), asset_kind, amount)?;
+            let agreed = attestations.iter().filter(|(_, t)| t == &transfer).count();
+            if !Self::is_threshold_reached(agreed) {
+                <IncomingAttestations<T>>::insert(key, attestations);
+                return Ok(());
+            }
 
-            <Self as Store>::OcRequests::mutate(|v| v.push(OffchainRequest::OutgoingTransfer(from, receiver, asset_kind, amount, nonce)));
+            <IncomingAttestations<T>>::remove(key);
+            <ProcessedIrohaTxs<T>>::insert(key, true);
+            let IncomingTransfer { sender, receiver, receiver_source, asset_id, quantity } = transfer;
+            let credited = Self::credit_incoming_transfer(
+                iroha_tx_hash,
+                isi_index,
+                sender.clone(),
+                receiver.clone(),
+                asset_id,
+                quantity,
+            );
+            // memos and links may name any account, so they don't change the default mapping
+            if credited
+                && receiver_source == ReceiverSource::Signatory
+                && <Accounts<T>>::get(&sender) == T::AccountId::default()
+            {
+                <Accounts<T>>::insert(sender, receiver);
+            }
             Ok(())
         }
 
+        /// Reports a transfer into the bridge account under an `If` instruction, so that whether
+        /// Iroha executed it can't be told from the block. With `Threshold` reports of the same
+        /// transfer it waits for `root` to resolve it.
         #[weight = 0]
-        pub fn incoming_transfer(origin, sender: iroha::AccountId, receiver: T::AccountId, asset_kind: treasury::AssetKind, amount: u128) -> DispatchResult {
-            debug::debug!("called force_transfer");
+        pub fn conditional_incoming_transfer(
+            origin,
+            iroha_tx_hash: T::Hash,
+            isi_index: u32,
+            sender: iroha::AccountId,
+            definition_id: iroha::AssetDefinitionId,
+            quantity: u32,
+        ) -> DispatchResult {
+            debug::debug!("called conditional_incoming_transfer");
             let author = ensure_signed(origin)?;
-            if Self::is_authority(&author) {
-                debug::info!("Incoming transfer from {} to {:?} with {:?} {:?}", sender, receiver, amount, asset_kind);
-                if <Accounts<T>>::get(&sender) == T::AccountId::default() {
-                    <Accounts<T>>::insert(sender.clone(), receiver.clone());
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let key = (iroha_tx_hash, isi_index);
+            ensure!(!<ProcessedIrohaTxs<T>>::get(key), <Error<T>>::IrohaTxAlreadyProcessed);
+            let report = (iroha_tx_hash, isi_index, sender, definition_id, quantity);
+            let mut votes = <ConditionalTransferVotes<T>>::get(&report);
+            ensure!(!votes.contains(&author), <Error<T>>::AlreadyReported);
+
+            votes.push(author);
+            if !Self::is_threshold_reached(votes.len()) {
+                <ConditionalTransferVotes<T>>::insert(&report, votes);
+                return Ok(());
+            }
+            <ConditionalTransferVotes<T>>::remove(&report);
+            <ProcessedIrohaTxs<T>>::insert(key, true);
+            let (_, _, sender, definition_id, quantity) = report;
+            <ConditionalIncomingTransfers<T>>::insert(key, (sender, definition_id, quantity));
+            Self::deposit_event(RawEvent::ConditionalIncomingTransfer(iroha_tx_hash, isi_index));
+            Ok(())
+        }
+
+        /// Resolves a reported transfer under an `If` instruction: it is credited to `receiver`
+        /// if Iroha executed it, and dropped if `receiver` is `None`.
+        #[weight = 0]
+        pub fn resolve_conditional_incoming_transfer(
+            origin,
+            iroha_tx_hash: T::Hash,
+            isi_index: u32,
+            receiver: Option<T::AccountId>,
+        ) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            let key = (iroha_tx_hash, isi_index);
+            let (sender, definition_id, quantity) = <ConditionalIncomingTransfers<T>>::get(key)
+                .ok_or(<Error<T>>::UnknownConditionalTransfer)?;
+            match receiver {
+                Some(receiver) => {
+                    let asset_id = <treasury::Module<T>>::asset_id(&definition_id)?;
+                    <ConditionalIncomingTransfers<T>>::remove(key);
+                    Self::credit_incoming_transfer(iroha_tx_hash, isi_index, sender, receiver, asset_id, u128::from(quantity));
+                }
+                None => {
+                    <ConditionalIncomingTransfers<T>>::remove(key);
+                    Self::deposit_event(RawEvent::ConditionalIncomingTransferDropped(iroha_tx_hash, isi_index));
                 }
-                <treasury::Module<T>>::mint(receiver.clone(), asset_kind, amount)?;
-                Self::deposit_event(RawEvent::IncomingTransfer(sender, receiver, asset_kind, amount));
+            }
+            Ok(())
+        }
+
+        /// Votes to send an incoming transfer that can't be credited back to its Iroha sender.
+        /// With `Threshold` votes for the same refund its Iroha transaction is queued.
+        #[weight = 0]
+        pub fn refund_incoming_transfer(
+            origin,
+            iroha_tx_hash: T::Hash,
+            isi_index: u32,
+            sender: iroha::AccountId,
+            definition_id: iroha::AssetDefinitionId,
+            quantity: u32,
+        ) -> DispatchResult {
+            debug::debug!("called refund_incoming_transfer");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            Self::vote_incoming_transfer_refund(author, iroha_tx_hash, isi_index, sender, definition_id, quantity)
+        }
+
+        /// Requests to link an Iroha account to the caller, so that transfers from it are
+        /// credited to the caller. `signature` is made over `link_message` by a key of the Iroha
+        /// account. The link takes effect once the authorities checked that the key is a
+        /// signatory of the account, and is only followed while it stays one.
+        #[weight = 0]
+        pub fn link_iroha_account(
+            origin,
+            iroha_account: iroha::AccountId,
+            signature: iroha_crypto::Signature,
+        ) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let message = Self::link_message(&iroha_account, &who);
+            ensure!(utils::verify_iroha_sig(&message, &signature), <Error<T>>::InvalidLinkSignature);
+            if let Some((linked, _)) = <LinkedAccounts<T>>::get(&iroha_account) {
+                ensure!(linked == who, <Error<T>>::IrohaAccountAlreadyLinked);
+            }
+            <PendingLinks<T>>::insert((iroha_account.clone(), who.clone()), signature.public_key);
+            Self::deposit_event(RawEvent::IrohaAccountLinkRequested(who, iroha_account));
+            Ok(())
+        }
+
+        /// Reports whether the key of a pending link is a signatory of the Iroha account. With
+        /// `Threshold` matching reports the link takes effect or is rejected.
+        #[weight = 0]
+        pub fn check_iroha_account_link(
+            origin,
+            iroha_account: iroha::AccountId,
+            who: T::AccountId,
+            public_key: iroha_crypto::PublicKey,
+            is_signatory: bool,
+        ) -> DispatchResult {
+            debug::debug!("called check_iroha_account_link");
+            let author = ensure_signed(origin)?;
+            ensure!(Self::is_authority(&author), <Error<T>>::NotAnAuthority);
+            let link = (iroha_account.clone(), who.clone());
+            ensure!(<PendingLinks<T>>::get(&link) == Some(public_key.clone()), <Error<T>>::UnknownLinkRequest);
+            let vote = (iroha_account.clone(), who.clone(), public_key.clone(), is_signatory);
+            let mut votes = <LinkCheckVotes<T>>::get(&vote);
+            ensure!(!votes.contains(&author), <Error<T>>::AlreadyReported);
+
+            votes.push(author);
+            if !Self::is_threshold_reached(votes.len()) {
+                <LinkCheckVotes<T>>::insert(&vote, votes);
+                return Ok(());
+            }
+            <LinkCheckVotes<T>>::remove(&vote);
+            <LinkCheckVotes<T>>::remove(&(iroha_account.clone(), who.clone(), public_key.clone(), !is_signatory));
+            <PendingLinks<T>>::remove(&link);
+            // another account may have been linked since the request
+            let linked_to_other = <LinkedAccounts<T>>::get(&iroha_account).map_or(false, |(linked, _)| linked != who);
+            if is_signatory && !linked_to_other {
+                <LinkedAccounts<T>>::insert(&iroha_account, (who.clone(), public_key));
+                Self::deposit_event(RawEvent::IrohaAccountLinked(who, iroha_account));
             } else {
-               debug::warn!("{:?} is not an authority", author);
+                Self::deposit_event(RawEvent::IrohaAccountLinkRejected(who, iroha_account));
             }
             Ok(())
         }
 
+        /// Removes the caller's link to an Iroha account, or its pending link request.
+        #[weight = 0]
+        pub fn unlink_iroha_account(origin, iroha_account: iroha::AccountId) -> DispatchResult {
+            let who = ensure_signed(origin)?;
+            let link = (iroha_account.clone(), who.clone());
+            let linked = <LinkedAccounts<T>>::get(&iroha_account).map_or(false, |(linked, _)| linked == who);
+            ensure!(linked || <PendingLinks<T>>::contains_key(&link), <Error<T>>::IrohaAccountNotLinked);
+            <PendingLinks<T>>::remove(&link);
+            if linked {
+                <LinkedAccounts<T>>::remove(&iroha_account);
+            }
+            Self::deposit_event(RawEvent::IrohaAccountUnlinked(who, iroha_account));
+            Ok(())
+        }
+
+        /// Adds an authority signing Iroha transactions with `key`.
+        #[weight = 0]
+        pub fn add_authority(origin, who: T::AccountId, key: iroha_crypto::PublicKey) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            ensure!(!Self::is_authority(&who), <Error<T>>::AlreadyAnAuthority);
+            ensure!(!<Authorities<T>>::iter().any(|(_, k)| k == key), <Error<T>>::DuplicateAuthorityKey);
+            <Authorities<T>>::insert(&who, key.clone());
+            AuthorityCount::mutate(|n| *n += 1);
+            Self::queue_signatory_update(SignatoryUpdate::Add(key));
+            Self::deposit_event(RawEvent::AuthorityAdded(who));
+            Ok(())
+        }
+
+        /// Removes an authority. The remaining authorities must still reach `Threshold`.
         #[weight = 0]
-        pub fn add_authority(origin, who: T::AccountId) -> DispatchResult {
+        pub fn remove_authority(origin, who: T::AccountId) -> DispatchResult {
             let _ = ensure_root(origin)?;
-            if !Self::is_authority(&who) {
-                <Authorities<T>>::mutate(|l| l.push(who));
+            let key = <Authorities<T>>::get(&who).ok_or(<Error<T>>::NotAnAuthority)?;
+            Self::ensure_valid_threshold(Self::authority_count() - 1, Self::target_threshold())?;
+            <Authorities<T>>::remove(&who);
+            AuthorityCount::mutate(|n| *n -= 1);
+            Self::queue_signatory_update(SignatoryUpdate::Remove(key));
+            Self::deposit_event(RawEvent::AuthorityRemoved(who));
+            Ok(())
+        }
+
+        /// Replaces the authority set and the threshold at once. New Iroha keys are added to the
+        /// bridge account before its quorum is updated and the replaced keys are removed. The new
+        /// threshold takes effect once the keys are added and the quorum is updated on Iroha.
+        #[weight = 0]
+        pub fn set_authorities(origin, authorities: Vec<(T::AccountId, iroha_crypto::PublicKey)>, threshold: u32) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            let accounts: BTreeSet<_> = authorities.iter().map(|(who, _)| who).collect();
+            ensure!(accounts.len() == authorities.len(), <Error<T>>::DuplicateAuthority);
+            let keys: BTreeSet<_> = authorities.iter().map(|(_, key)| key).collect();
+            ensure!(keys.len() == authorities.len(), <Error<T>>::DuplicateAuthorityKey);
+            Self::ensure_valid_threshold(authorities.len() as u32, threshold)?;
+
+            let old_authorities: Vec<_> = <Authorities<T>>::iter().collect();
+            let old_keys: BTreeSet<_> = old_authorities.iter().map(|(_, key)| key.clone()).collect();
+            for (who, key) in &authorities {
+                let old_key = <Authorities<T>>::get(who);
+                if old_key.as_ref() == Some(key) {
+                    continue;
+                }
+                // a key moving between authorities already is a signatory
+                if !old_keys.contains(key) {
+                    Self::queue_signatory_update(SignatoryUpdate::Add(key.clone()));
+                }
+                <Authorities<T>>::insert(who, key.clone());
+                match old_key {
+                    Some(_) => Self::deposit_event(RawEvent::AuthorityKeyChanged(who.clone())),
+                    None => Self::deposit_event(RawEvent::AuthorityAdded(who.clone())),
+                }
+            }
+            if threshold != Self::target_threshold() {
+                Self::queue_threshold_update(threshold);
+            }
+            for (who, old_key) in old_authorities {
+                let new_key = authorities.iter().find(|(w, _)| w == &who).map(|(_, key)| key);
+                if new_key == Some(&old_key) {
+                    continue;
+                }
+                if !keys.contains(&old_key) {
+                    Self::queue_signatory_update(SignatoryUpdate::Remove(old_key));
+                }
+                if new_key.is_none() {
+                    <Authorities<T>>::remove(&who);
+                    Self::deposit_event(RawEvent::AuthorityRemoved(who));
+                }
             }
+            AuthorityCount::put(authorities.len() as u32);
             Ok(())
         }
 
+        /// Sets the number of distinct authorities that must agree before the bridge acts. It
+        /// takes effect once the Iroha bridge account's quorum is updated, so it can't exceed the
+        /// signatories the account has on Iroha.
+        #[weight = 0]
+        pub fn set_threshold(origin, threshold: u32) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            Self::ensure_valid_threshold(Self::authority_count(), threshold)?;
+            ensure!(threshold as usize <= Self::committed_signatories().len(), <Error<T>>::InvalidThreshold);
+            Self::queue_threshold_update(threshold);
+            Ok(())
+        }
+
+        /// Adds a peer to the current Iroha peer set.
         #[weight = 0]
         pub fn add_iroha_peer(origin, peer: iroha_crypto::PublicKey) -> DispatchResult {
             let _ = ensure_root(origin)?;
             if !Self::is_iroha_peer(&peer) {
-                IrohaPeers::mutate(|l| l.push(peer));
+                IrohaPeers::mutate(|l| l.push(peer.clone()));
+                Self::deposit_event(RawEvent::IrohaPeerAdded(peer));
+            }
+            Ok(())
+        }
+
+        /// Removes a peer from the Iroha peer set for blocks starting at `activation_height`.
+        /// Blocks below that height are still verified against the previous set.
+        #[weight = 0]
+        pub fn remove_iroha_peer(
+            origin,
+            peer: iroha_crypto::PublicKey,
+            activation_height: u64,
+        ) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            let mut peers = Self::iroha_peers();
+            let index = peers.iter().position(|p| p == &peer).ok_or(<Error<T>>::UnknownIrohaPeer)?;
+            peers.remove(index);
+            Self::rotate_iroha_peers(peers, activation_height)?;
+            Self::deposit_event(RawEvent::IrohaPeerRemoved(peer, activation_height));
+            Ok(())
+        }
+
+        /// Replaces the Iroha peer set for blocks starting at `activation_height`.
+        /// Blocks below that height are still verified against the previous set.
+        #[weight = 0]
+        pub fn set_iroha_peers(
+            origin,
+            peers: Vec<iroha_crypto::PublicKey>,
+            activation_height: u64,
+        ) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            let unique: BTreeSet<_> = peers.iter().collect();
+            ensure!(unique.len() == peers.len(), <Error<T>>::DuplicateIrohaPeer);
+            Self::rotate_iroha_peers(peers, activation_height)?;
+            Self::deposit_event(RawEvent::IrohaPeersSet(activation_height));
+            Ok(())
+        }
+
+        /// Sets the Iroha account the bridge sends transactions as. Its signatories and signature
+        /// check condition have to be set up on the Iroha side. Only the current account's
+        /// transactions are recognized in Iroha blocks, so it can't change while any of them or
+        /// an outgoing transfer is pending.
+        #[weight = 0]
+        pub fn set_bridge_account(origin, account: iroha::AccountId) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            ensure!(
+                <PendingIrohaTxs<T>>::iter().next().is_none() && <PendingOutgoing<T>>::iter().next().is_none(),
+                <Error<T>>::BridgeAccountInUse
+            );
+            BridgeAccount::put(account.clone());
+            Self::deposit_event(RawEvent::BridgeAccountChanged(account));
+            Ok(())
+        }
+
+        /// Sets the Iroha bridge definition incoming and outgoing transfers are registered with.
+        #[weight = 0]
+        pub fn set_bridge_definition(origin, definition: BridgeDefinitionId) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            BridgeDefinition::put(definition.clone());
+            Self::deposit_event(RawEvent::BridgeDefinitionChanged(definition));
+            Ok(())
+        }
+
+        #[weight = 0]
+        pub fn set_iroha_endpoint(origin, endpoint: Vec<u8>) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            ensure!(utils::is_valid_iroha_endpoint(&endpoint), <Error<T>>::InvalidIrohaEndpoint);
+            IrohaEndpoint::put(endpoint.clone());
+            Self::deposit_event(RawEvent::IrohaEndpointChanged(endpoint));
+            Ok(())
+        }
+
+        #[weight = 0]
+        pub fn set_iroha_peers_threshold(origin, threshold: Option<u32>) -> DispatchResult {
+            let _ = ensure_root(origin)?;
+            Self::ensure_valid_iroha_peers_threshold(threshold, Self::iroha_peers().len())?;
+            match threshold {
+                Some(t) => IrohaPeersThreshold::put(t),
+                None => IrohaPeersThreshold::kill(),
             }
+            Self::deposit_event(RawEvent::IrohaPeersThresholdChanged(threshold));
             Ok(())
         }
 
@@ -261,21 +1150,26 @@ decl_module! {
 
 impl<T: Trait> Module<T> {
     fn offchain() {
-        for e in <Self as Store>::OcRequests::get() {
-            match e {
-                OffchainRequest::OutgoingTransfer(from, to, asset_kind, amount, nonce) => {
-                    if let Err(e) =
-                        Self::handle_outgoing_transfer(from.clone(), to, asset_kind, amount, nonce)
-                    {
-                        debug::warn!("{:?}", e);
-                        if let Err(e) = <treasury::Module<T>>::unlock(from, asset_kind, amount) {
-                            debug::error!("Failed to unlock funds: {:?}", e);
-                        }
-                    }
-                }
+        for (request_id, transfer) in <PendingOutgoing<T>>::iter() {
+            if transfer.status != OutgoingTransferStatus::Requested {
+                continue;
+            }
+            let res = Self::approve_outgoing_transfer_ocw(request_id, transfer.retries);
+            if let Err(e) = res {
+                debug::warn!(
+                    "Failed to process outgoing transfer {}: {:?}",
+                    request_id,
+                    e
+                );
             }
         }
 
+        Self::send_iroha_txs();
+
+        if let Err(e) = Self::check_iroha_account_links() {
+            debug::warn!("Failed to check Iroha account links: {:?}", e);
+        }
+
         match Self::fetch_iroha() {
             Ok(_) => (),
             Err(e) => {
@@ -286,107 +1180,354 @@ impl<T: Trait> Module<T> {
 
     fn handle_block(block: ValidBlock) -> Result<(), Error<T>> {
         debug::debug!("Handling Iroha block at height {}", block.header.height);
+        // Iroha's time, telling which queued transactions expired
+        let block_timestamp = block.header.timestamp as u64;
+        let bridge_account_id = Self::bridge_account_id();
+        let bridge_tx_hashes: Vec<T::Hash> = block
+            .transactions
+            .iter()
+            .filter(|tx| tx.payload.account_id == bridge_account_id)
+            .map(|tx| T::Hashing::hash(&tx.payload.encode()))
+            .collect();
+        Self::check_queued_iroha_txs(&bridge_tx_hashes, block_timestamp)?;
         for tx in block.transactions {
+            let tx_hash = T::Hashing::hash(&tx.payload.encode());
             let _author_id = tx.payload.account_id;
-            let bridge_account_id = iroha::AccountId::new("bridge", "polkadot");
-            for isi in tx.payload.instructions {
-                match isi {
-                    iroha::Instruction::Account(AccountInstruction::TransferAsset(
-                        from,
-                        to,
-                        asset,
-                    )) => {
-                        debug::info!(
-                            "Detected outgoing {} transfer from {}",
-                            asset.id.definition_id.name,
-                            from
-                        );
-                        if to == bridge_account_id {
-                            let asset_kind = AssetKind::try_from(&asset.id.definition_id)
-                                .map_err(|_| <Error<T>>::Other)?;
-
-                            let quantity = asset.quantity;
-                            let amount = quantity as u128;
-
-                            let signer = Signer::<T, T::AuthorityId>::any_account();
-                            if !signer.can_sign() {
-                                debug::error!("No local account available");
-                                return Err(<Error<T>>::Other);
-                            }
-
-                            let recipient_account = {
-                                let mut recipient_account = <Self as Store>::Accounts::get(&from);
-                                if recipient_account == T::AccountId::default() {
-                                    let account_query = GetAccount::build_request(from.clone());
-                                    let query_result = Self::send_query(account_query)?;
-                                    debug::trace!("query result: {:?}", query_result);
-                                    let queried_acc = match query_result {
-                                        iroha::QueryResult::GetAccount(res) => res.account,
-                                        _ => return Err(<Error<T>>::Other),
-                                    };
-                                    let account_pk =
-                                        queried_acc.signatories.first().ok_or(<Error<T>>::Other)?;
-                                    let account_id =
-                                        utils::substrate_account_id_from_iroha_pk::<T>(account_pk);
-                                    <Accounts<T>>::insert(from.clone(), account_id.clone());
-                                    recipient_account = account_id;
-                                }
-                                recipient_account
-                            };
-
-                            let result = signer.send_signed_transaction(|acc| {
-                                debug::debug!("signer {:?}", acc.id);
-                                Call::incoming_transfer(
-                                    from.clone(),
-                                    recipient_account.clone(),
-                                    asset_kind,
-                                    amount,
-                                )
-                            });
-
-                            match result {
-                                Some((_acc, Ok(_))) => {
-                                    let bridge_def_id =
-                                        BridgeDefinitionId::new(&bridge_account_id.domain_name);
-                                    let tx = ExternalTransaction {
-                                        hash: "".into(),
-                                        payload: vec![],
-                                    };
-                                    let instructions = vec![bridge::isi::handle_outgoing_transfer(
-                                        &bridge_def_id,
-                                        &asset_kind.definition_id(),
-                                        quantity,
-                                        0,
-                                        &tx,
-                                    )];
-                                    let resp = Self::send_instructions(instructions);
-                                    if resp.is_err() {
-                                        debug::error!(
-                                            "error while processing handle_outgoing_transfer ISI"
-                                        );
-                                        return Err(<Error<T>>::SubmitInstructionsFailed);
-                                    } else {
-                                        debug::error!("ok processing handle_outgoing_transfer ISI");
-                                    }
-                                }
-                                Some((acc, Err(e))) => {
-                                    debug::error!(
-                                        "[{:?}] Failed in signed_submit_number: {:?}",
-                                        acc.id,
-                                        e
-                                    );
-                                    return Err(<Error<T>>::SendSignedTransactionError);
-                                }
-                                _ => {
-                                    debug::error!("Failed in signed_submit_number");
-                                    return Err(<Error<T>>::SendSignedTransactionError);
-                                }
-                            };
+            let memo = Self::transfer_memo(&tx.payload.instructions);
+            let transfers = Self::bridge_transfers(tx.payload.instructions, &bridge_account_id);
+            for BridgeTransfer {
+                isi_index,
+                from,
+                asset,
+                conditional,
+            } in transfers
+            {
+                debug::info!(
+                    "Detected outgoing {} transfer from {}",
+                    asset.id.definition_id.name,
+                    from
+                );
+                if Self::processed_iroha_txs((tx_hash, isi_index)) {
+                    debug::info!(
+                        "Iroha transaction {:?} instruction {} is already processed",
+                        tx_hash,
+                        isi_index
+                    );
+                    continue;
+                }
+                if conditional {
+                    debug::warn!(
+                        "Reporting conditional transfer in Iroha transaction {:?} instruction {}",
+                        tx_hash,
+                        isi_index
+                    );
+                    Self::report_conditional_transfer_ocw(tx_hash, isi_index, from, asset)?;
+                    continue;
+                }
+                let memo_recipient = match &memo {
+                    Some(memo) => match utils::decode_ss58::<T>(memo) {
+                        Some(account_id) => Some((account_id, ReceiverSource::Memo)),
+                        None => {
+                            debug::warn!(
+                                "Refunding transfer in Iroha transaction {:?} instruction {} with invalid memo {}",
+                                tx_hash,
+                                isi_index,
+                                memo
+                            );
+                            Self::refund_incoming_transfer_ocw(tx_hash, isi_index, from, asset)?;
+                            continue;
                         }
+                    },
+                    None => None,
+                };
+                // transfers that can't be credited, e.g. of an unregistered or disabled asset or
+                // releasing more than the escrow holds, are sent back instead of attested
+                let creditable =
+                    <treasury::Module<T>>::asset_id(&asset.id.definition_id).and_then(|asset_id| {
+                        let amount = <treasury::Module<T>>::from_iroha_quantity(
+                            asset_id,
+                            u128::from(asset.quantity),
+                        )?;
+                        <treasury::Module<T>>::ensure_can_credit(asset_id, amount)?;
+                        Ok(asset_id)
+                    });
+                let asset_id = match creditable {
+                    Ok(asset_id) => asset_id,
+                    Err(e) => {
+                        debug::warn!(
+                            "Refunding transfer in Iroha transaction {:?} instruction {} that can't be credited: {:?}",
+                            tx_hash,
+                            isi_index,
+                            e
+                        );
+                        Self::refund_incoming_transfer_ocw(tx_hash, isi_index, from, asset)?;
+                        continue;
                     }
-                    _ => (),
+                };
+
+                let quantity = asset.quantity;
+                let signer = Signer::<T, T::AuthorityId>::any_account();
+                if !signer.can_sign() {
+                    debug::error!("No local account available");
+                    return Err(<Error<T>>::Other);
                 }
+
+                let (recipient_account, receiver_source) = match memo_recipient {
+                    Some(recipient) => recipient,
+                    None => Self::resolve_recipient(&from)?,
+                };
+
+                let result = signer.send_signed_transaction(|acc| {
+                    debug::debug!("signer {:?}", acc.id);
+                    Call::incoming_transfer(
+                        tx_hash,
+                        isi_index,
+                        from.clone(),
+                        recipient_account.clone(),
+                        receiver_source,
+                        asset_id,
+                        u128::from(quantity),
+                    )
+                });
+
+                match result {
+                    Some((_acc, Ok(_))) => (),
+                    Some((acc, Err(e))) => {
+                        debug::error!("[{:?}] Failed in signed_submit_number: {:?}", acc.id, e);
+                        return Err(<Error<T>>::SendSignedTransactionError);
+                    }
+                    _ => {
+                        debug::error!("Failed in signed_submit_number");
+                        return Err(<Error<T>>::SendSignedTransactionError);
+                    }
+                };
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the Substrate account credited with a transfer from `from` without a memo: the
+    /// account linked to `from` while the linking key is a signatory of `from`, or else the
+    /// account derived from the first signatory of `from`.
+    fn resolve_recipient(
+        from: &iroha::AccountId,
+    ) -> Result<(T::AccountId, ReceiverSource), Error<T>> {
+        let linked = Self::linked_account(from);
+        if linked.is_none() {
+            let recipient_account = <Self as Store>::Accounts::get(from);
+            if recipient_account != T::AccountId::default() {
+                return Ok((recipient_account, ReceiverSource::Signatory));
+            }
+        }
+        let signatories = Self::iroha_signatories(from)?;
+        if let Some((account_id, public_key)) = linked {
+            if signatories.contains(&public_key) {
+                return Ok((account_id, ReceiverSource::Link));
             }
+            debug::warn!(
+                "The key linking Iroha account {} is no longer its signatory",
+                from
+            );
+        }
+        let account_pk = signatories.first().ok_or(<Error<T>>::Other)?;
+        let account_id = utils::substrate_account_id_from_iroha_pk::<T>(account_pk);
+        Ok((account_id, ReceiverSource::Signatory))
+    }
+
+    /// Queries the signatories of an Iroha account.
+    fn iroha_signatories(
+        account_id: &iroha::AccountId,
+    ) -> Result<Vec<iroha_crypto::PublicKey>, Error<T>> {
+        let account_query = GetAccount::build_request(account_id.clone());
+        let query_result = Self::send_query(account_query)?;
+        debug::trace!("query result: {:?}", query_result);
+        match query_result {
+            iroha::QueryResult::GetAccount(res) => Ok(res.account.signatories),
+            _ => Err(<Error<T>>::Other),
+        }
+    }
+
+    /// Message a key of `iroha_account` signs to link the account to `who`. It is bound to this
+    /// chain by its genesis hash.
+    pub fn link_message(iroha_account: &iroha::AccountId, who: &T::AccountId) -> Vec<u8> {
+        let genesis_hash = <system::Module<T>>::block_hash(T::BlockNumber::zero());
+        (LINK_MESSAGE_DOMAIN, genesis_hash, iroha_account, who).encode()
+    }
+
+    /// Reports once for every pending link whether its key is a signatory of the Iroha account.
+    fn check_iroha_account_links() -> Result<(), Error<T>> {
+        for ((iroha_account, who), public_key) in <PendingLinks<T>>::iter() {
+            let link_hash = T::Hashing::hash(&(&iroha_account, &who, &public_key).encode());
+            let key = format!(
+                "iroha-bridge-ocw::link-checked-{}",
+                utils::hex_hash(&link_hash)
+            );
+            let s_checked = StorageValueRef::persistent(key.as_bytes());
+            if s_checked.get::<bool>().flatten().unwrap_or(false) {
+                continue;
+            }
+            let is_signatory = match Self::iroha_signatories(&iroha_account) {
+                Ok(signatories) => signatories.contains(&public_key),
+                Err(e) => {
+                    debug::warn!("Failed to query Iroha account {}: {:?}", iroha_account, e);
+                    continue;
+                }
+            };
+            Self::send_signed(Call::check_iroha_account_link(
+                iroha_account,
+                who,
+                public_key,
+                is_signatory,
+            ))?;
+            s_checked.set(&true);
+        }
+        Ok(())
+    }
+
+    /// Returns the memo of a transaction: the text of its first top-level `Notify` instruction.
+    /// It names the SS58 address the transaction's transfers to the bridge are credited to.
+    fn transfer_memo(instructions: &[iroha::Instruction]) -> Option<String> {
+        instructions.iter().find_map(|isi| match isi {
+            iroha::Instruction::Notify(memo) => Some(memo.clone()),
+            _ => None,
+        })
+    }
+
+    /// Reports once a transfer into the bridge account under an `If` instruction.
+    fn report_conditional_transfer_ocw(
+        tx_hash: T::Hash,
+        isi_index: u32,
+        from: iroha::AccountId,
+        asset: iroha::Asset,
+    ) -> Result<(), Error<T>> {
+        let key = format!(
+            "iroha-bridge-ocw::conditional-reported-{}-{}",
+            utils::hex_hash(&tx_hash),
+            isi_index
+        );
+        let s_reported = StorageValueRef::persistent(key.as_bytes());
+        if s_reported.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
+        }
+        Self::send_signed(Call::conditional_incoming_transfer(
+            tx_hash,
+            isi_index,
+            from,
+            asset.id.definition_id,
+            asset.quantity,
+        ))?;
+        s_reported.set(&true);
+        Ok(())
+    }
+
+    /// Returns the transfers into `bridge_account_id` among `instructions`, including the ones
+    /// nested in `Sequence`, `Pair` and `If` instructions.
+    fn bridge_transfers(
+        instructions: Vec<iroha::Instruction>,
+        bridge_account_id: &iroha::AccountId,
+    ) -> Vec<BridgeTransfer> {
+        let top_level_len = instructions.len() as u32;
+        let mut transfers = Vec::new();
+        let mut nested = Vec::new();
+        for (isi_index, isi) in instructions.into_iter().enumerate() {
+            let found = match isi {
+                iroha::Instruction::Sequence(_)
+                | iroha::Instruction::Pair(..)
+                | iroha::Instruction::If(..) => &mut nested,
+                _ => &mut transfers,
+            };
+            let len = found.len();
+            Self::collect_bridge_transfers(isi, bridge_account_id, false, found);
+            for transfer in &mut found[len..] {
+                transfer.isi_index = isi_index as u32;
+            }
+        }
+        for (i, mut transfer) in nested.into_iter().enumerate() {
+            transfer.isi_index = top_level_len + i as u32;
+            transfers.push(transfer);
+        }
+        transfers
+    }
+
+    fn collect_bridge_transfers(
+        isi: iroha::Instruction,
+        bridge_account_id: &iroha::AccountId,
+        conditional: bool,
+        found: &mut Vec<BridgeTransfer>,
+    ) {
+        match isi {
+            iroha::Instruction::Account(AccountInstruction::TransferAsset(from, to, asset))
+            | iroha::Instruction::Asset(AssetInstruction::TransferAsset(from, to, asset)) => {
+                if &to == bridge_account_id {
+                    found.push(BridgeTransfer {
+                        isi_index: 0,
+                        from,
+                        asset,
+                        conditional,
+                    });
+                }
+            }
+            iroha::Instruction::Sequence(instructions) => {
+                for isi in instructions {
+                    Self::collect_bridge_transfers(isi, bridge_account_id, conditional, found);
+                }
+            }
+            iroha::Instruction::Pair(left, right) => {
+                Self::collect_bridge_transfers(*left, bridge_account_id, conditional, found);
+                Self::collect_bridge_transfers(*right, bridge_account_id, conditional, found);
+            }
+            // only one branch is executed, and the condition only if it succeeds
+            iroha::Instruction::If(condition, then, otherwise) => {
+                Self::collect_bridge_transfers(*condition, bridge_account_id, true, found);
+                Self::collect_bridge_transfers(*then, bridge_account_id, true, found);
+                if let Some(otherwise) = otherwise {
+                    Self::collect_bridge_transfers(*otherwise, bridge_account_id, true, found);
+                }
+            }
+            _ => (),
+        }
+    }
+
+    /// Checks that `block` is signed by at least `threshold` distinct peers from `peers` and by
+    /// no one else.
+    fn verify_block(
+        block: &ValidBlock,
+        peers: &BTreeSet<iroha_crypto::PublicKey>,
+        threshold: usize,
+    ) -> Result<(), Error<T>> {
+        let block_hash = T::Hashing::hash(&block.header.encode());
+        let mut signers = BTreeSet::new();
+        for sig in block.signatures.values() {
+            debug::debug!("block signer: {:?}", sig.public_key);
+            if !peers.contains(&sig.public_key) {
+                debug::error!(
+                    "Block {} is signed by unknown peer {:?}",
+                    block.header.height,
+                    sig.public_key
+                );
+                return Err(<Error<T>>::InvalidBlockSignature);
+            }
+            if !signers.insert(sig.public_key.clone()) {
+                debug::error!(
+                    "Block {} is signed twice by peer {:?}",
+                    block.header.height,
+                    sig.public_key
+                );
+                return Err(<Error<T>>::InvalidBlockSignature);
+            }
+            if !utils::verify_iroha_sig(block_hash.as_ref(), &sig) {
+                debug::error!("Invalid signature of block: {:?}", block_hash);
+                return Err(<Error<T>>::InvalidBlockSignature);
+            }
+        }
+        if signers.len() < threshold {
+            debug::error!(
+                "Block {} has {} valid signatures, {} required",
+                block.header.height,
+                signers.len(),
+                threshold
+            );
+            return Err(<Error<T>>::NotEnoughBlockSignatures);
         }
         Ok(())
     }
@@ -394,7 +1535,7 @@ impl<T: Trait> Module<T> {
     fn fetch_blocks(from_height: u64) -> Result<Vec<ValidBlock>, Error<T>> {
         let null_pk = iroha_crypto::PublicKey::try_from(vec![0u8; 32]).unwrap();
         let get_blocks = BlockMessage::GetBlocksFromHeight(from_height, PeerId::new("", &null_pk));
-        let msg = Self::http_request::<_, BlockMessage>(BLOCK_ENDPOINT, &get_blocks)?;
+        let msg = Self::http_request::<_, BlockMessage>(BLOCK_PATH, &get_blocks)?;
         let blocks = match msg {
             BlockMessage::ShareBlocks(blocks, _) => blocks,
             _ => {
@@ -402,148 +1543,520 @@ impl<T: Trait> Module<T> {
                 return Err(<Error<T>>::Other);
             }
         };
-        let mut expected_peers: BTreeSet<iroha_crypto::PublicKey> =
-            IrohaPeers::get().iter().cloned().collect();
-        debug::debug!("expected_peers: {:?}", expected_peers);
-        for block in blocks.clone() {
-            for (iroha_pk, (pk, sig)) in block.signatures.values().iter().cloned().map(|sig| {
-                (
-                    sig.public_key.clone(),
-                    utils::iroha_sig_to_substrate_sig::<T>(sig),
-                )
-            }) {
-                debug::debug!("block signer: {:?}", iroha_pk);
-                if !expected_peers.remove(&iroha_pk) {
-                    return Err(<Error<T>>::InvalidBlockSignature);
-                }
-                let block_hash = T::Hashing::hash(&block.header.encode());
-                if !T::AuthorityId::verify(block_hash.as_ref(), pk, sig) {
-                    debug::error!("Invalid signature of block: {:?}", block_hash);
-                    return Err(<Error<T>>::InvalidBlockSignature);
-                }
-            }
+        for block in &blocks {
+            let peers: BTreeSet<iroha_crypto::PublicKey> =
+                Self::iroha_peers_at(block.header.height)
+                    .into_iter()
+                    .collect();
+            let threshold = Self::iroha_peers_threshold()
+                .map(|t| t as usize)
+                .unwrap_or_else(|| utils::bft_threshold(peers.len()));
+            debug::debug!("expected peers: {:?}, threshold: {}", peers, threshold);
+            Self::verify_block(block, &peers, threshold)?;
         }
-        // TODO
-        // if !expected_peers.is_empty() {
-        //     return Err(<Error<T>>::InvalidBlockSignature);
-        // }
         debug::debug!("Blocks are verified");
         Ok(blocks)
     }
 
+    /// Fetches new Iroha blocks and handles them one by one, checking that each block extends
+    /// the last processed one. The height and hash of the last processed block are kept in the
+    /// offchain storage, so on a gap or a fork nothing past the last good block is handled.
     fn fetch_iroha() -> Result<(), Error<T>> {
         let s_last_fetched_height =
             StorageValueRef::persistent(b"iroha-bridge-ocw::last-fetched-height");
-        let latest_height = s_last_fetched_height.get::<u64>().flatten().unwrap_or(0);
-        let blocks = Self::fetch_blocks(latest_height)?;
+        let s_last_fetched_hash =
+            StorageValueRef::persistent(b"iroha-bridge-ocw::last-fetched-hash");
+        let mut last_height = s_last_fetched_height.get::<u64>().flatten();
+        let mut last_hash = s_last_fetched_hash.get::<T::Hash>().flatten();
+        let blocks = Self::fetch_blocks(last_height.unwrap_or(0))?;
 
-        if !blocks.is_empty() {
-            let new_last_height = blocks.last().unwrap().header.height;
-            for block in blocks {
-                Self::handle_block(block)?;
+        for block in blocks {
+            let height = block.header.height;
+            let block_hash = T::Hashing::hash(&block.header.encode());
+            if let Some(last_height) = last_height {
+                if height <= last_height {
+                    if height == last_height && last_hash.map_or(false, |h| h != block_hash) {
+                        debug::error!(
+                            "Iroha block {} has hash {:?}, but {:?} was processed at this height",
+                            height,
+                            block_hash,
+                            last_hash
+                        );
+                        return Err(<Error<T>>::IrohaForkDetected);
+                    }
+                    // already processed
+                    continue;
+                }
+                if height != last_height + 1 {
+                    debug::error!(
+                        "Expected Iroha block {}, received block {}",
+                        last_height + 1,
+                        height
+                    );
+                    return Err(<Error<T>>::IrohaBlockGap);
+                }
             }
-            s_last_fetched_height.set(&new_last_height);
-            debug::debug!("fetched blocks");
+            if let Some(last_hash) = last_hash {
+                if &block.header.previous_block_hash[..] != last_hash.as_ref() {
+                    debug::error!(
+                        "Iroha block {} does not extend the last processed block {:?}",
+                        height,
+                        last_hash
+                    );
+                    return Err(<Error<T>>::IrohaForkDetected);
+                }
+            }
+
+            Self::handle_block(block)?;
+            s_last_fetched_height.set(&height);
+            s_last_fetched_hash.set(&block_hash);
+            last_height = Some(height);
+            last_hash = Some(block_hash);
+            debug::debug!("processed Iroha block {}", height);
         }
         Ok(())
     }
 
-    fn send_instructions(instructions: Vec<iroha::Instruction>) -> Result<(), Error<T>> {
-        debug::debug!("called send_instructions");
+    /// Unsigned transaction of the bridge account created at `creation_time`.
+    fn bridge_tx(
+        instructions: Vec<iroha::Instruction>,
+        creation_time: u64,
+    ) -> RequestedTransaction {
+        RequestedTransaction::new(
+            instructions,
+            Self::bridge_account_id(),
+            IROHA_TX_TIME_TO_LIVE_MS,
+            creation_time,
+        )
+    }
+
+    /// Signs `requested_tx` with the local authority keys and sends it to Iroha.
+    ///
+    /// Every authority sends the same transaction: the payload is built on chain when the
+    /// transaction is queued. Iroha keeps the transaction pending, merging the signatures of the
+    /// copies, until the bridge account's signature check condition is met.
+    fn send_iroha_tx(requested_tx: RequestedTransaction) -> Result<(), Error<T>> {
+        let requested_tx = Self::sign_iroha_tx(requested_tx)?;
+        Self::http_request::<_, ()>(INSTRUCTION_PATH, &requested_tx)
+    }
+
+    /// Adds the signatures of all local keys belonging to authorities to `requested_tx`. Keys
+    /// being removed still sign, they count on Iroha until the removal is committed.
+    fn sign_iroha_tx(
+        mut requested_tx: RequestedTransaction,
+    ) -> Result<RequestedTransaction, Error<T>> {
         let signer = Signer::<T, T::AuthorityIdEd>::all_accounts();
         if !signer.can_sign() {
             debug::error!("No local account available for signing");
             return Err(<Error<T>>::Other);
         }
-        let mut requested_tx = RequestedTransaction::new(
-            instructions,
-            account::Id::new("root", "global"),
-            10000,
-            sp_io::offchain::timestamp().unix_millis(),
-        );
+        let mut signing_keys = Self::committed_signatories();
+        signing_keys.extend(<Authorities<T>>::iter().map(|(_, key)| key));
         let payload_encoded = requested_tx.payload.encode();
         let sigs = signer.sign_message(&payload_encoded);
         for (acc, sig) in sigs {
-            debug::trace!("send_instructions acc [{}]: {:?}", acc.index, acc.public);
-            // TODO: multi-signature transaction
-            if acc.index == 0 {
-                let sig = utils::substrate_sig_to_iroha_sig::<T>((acc.public, sig));
+            debug::trace!("sign_iroha_tx acc [{}]: {:?}", acc.index, acc.public);
+            let sig = utils::substrate_sig_to_iroha_sig::<T>((acc.public, sig));
+            if signing_keys.contains(&sig.public_key) {
                 requested_tx.signatures.push(sig);
             }
         }
-
-        let resp = Self::http_request::<_, ()>(INSTRUCTION_ENDPOINT, &requested_tx)?;
-        Ok(resp)
+        if requested_tx.signatures.is_empty() {
+            debug::error!("No local key of an authority available for signing");
+            return Err(<Error<T>>::NotAnAuthority);
+        }
+        Ok(requested_tx)
     }
 
     fn send_query(query: iroha::QueryRequest) -> Result<iroha::QueryResult, Error<T>> {
-        let query_result = Self::http_request(QUERY_ENDPOINT, &query)?;
+        let query_result = Self::http_request(QUERY_PATH, &query)?;
         Ok(query_result)
     }
 
-    fn handle_outgoing_transfer(
-        from_account_id: T::AccountId,
-        to_account_id: iroha::AccountId,
-        asset_kind: AssetKind,
-        amount: u128,
-        nonce: u8,
+    fn approve_outgoing_transfer_ocw(request_id: RequestId, retries: u32) -> Result<(), Error<T>> {
+        let key = format!(
+            "iroha-bridge-ocw::outgoing-approved-{}-{}",
+            request_id, retries
+        );
+        let s_approved = StorageValueRef::persistent(key.as_bytes());
+        if s_approved.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
+        }
+        debug::info!("Approving outgoing transfer request {}", request_id);
+        Self::send_signed(Call::approve_outgoing_transfer(request_id))?;
+        s_approved.set(&true);
+        Ok(())
+    }
+
+    /// Sends the queued bridge account transactions to Iroha in order. Each one is sent once,
+    /// the first authority getting it accepted records that on chain. Whether it is executed is
+    /// only known once it shows up in a committed Iroha block, a failed request proves nothing.
+    fn send_iroha_txs() {
+        let now = Self::now_millis();
+        for (iroha_tx_id, pending) in Self::queued_iroha_txs() {
+            if Self::is_iroha_tx_expired(&pending.tx, now) {
+                continue;
+            }
+            let key = format!(
+                "iroha-bridge-ocw::iroha-tx-sent-{}",
+                utils::hex_hash(&pending.hash)
+            );
+            let s_sent = StorageValueRef::persistent(key.as_bytes());
+            if s_sent.get::<bool>().flatten().unwrap_or(false) {
+                continue;
+            }
+            if let Err(e) = Self::send_iroha_tx(pending.tx) {
+                debug::error!("Failed to send Iroha transaction {}: {:?}", iroha_tx_id, e);
+                continue;
+            }
+            s_sent.set(&true);
+            if !pending.sent {
+                if let Err(e) = Self::send_signed(Call::iroha_tx_sent(iroha_tx_id)) {
+                    debug::warn!(
+                        "Failed to report Iroha transaction {} sent: {:?}",
+                        iroha_tx_id,
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    /// Queued bridge account transactions in the order they were queued.
+    fn queued_iroha_txs() -> Vec<(IrohaTxId, PendingIrohaTx<T::Hash>)> {
+        let mut queued: Vec<_> = <PendingIrohaTxs<T>>::iter().collect();
+        queued.sort_by_key(|(iroha_tx_id, _)| *iroha_tx_id);
+        queued
+    }
+
+    /// Whether Iroha rejects `tx` at `now` (unix milliseconds) because of its time to live.
+    fn is_iroha_tx_expired(tx: &RequestedTransaction, now: u64) -> bool {
+        now > tx
+            .payload
+            .creation_time
+            .saturating_add(IROHA_TX_TIME_TO_LIVE_MS)
+    }
+
+    /// Checks the queued transactions against an Iroha block with `bridge_tx_hashes` created at
+    /// `block_timestamp`. A transaction missing from the blocks up to one created after its time
+    /// to live is confirmed to be rejected: an outgoing transfer no authority got accepted is
+    /// refunded, any other transaction is queued again.
+    fn check_queued_iroha_txs(
+        bridge_tx_hashes: &[T::Hash],
+        block_timestamp: u64,
     ) -> Result<(), Error<T>> {
-        debug::info!("Received transfer request");
+        let now = Self::now_millis();
+        for (iroha_tx_id, pending) in Self::queued_iroha_txs() {
+            if bridge_tx_hashes.contains(&pending.hash) {
+                Self::report_iroha_tx_committed(iroha_tx_id)?;
+                continue;
+            }
+            // the chain has to agree on the expiry for the report to be accepted
+            if !Self::is_iroha_tx_expired(&pending.tx, block_timestamp)
+                || !Self::is_iroha_tx_expired(&pending.tx, now)
+            {
+                continue;
+            }
+            match pending.purpose {
+                IrohaTxPurpose::OutgoingTransfer(request_id) if !pending.sent => {
+                    Self::refund_outgoing_transfer_ocw(request_id, "IrohaTxExpired")?
+                }
+                _ => Self::report_iroha_tx_expired(iroha_tx_id, &pending.hash)?,
+            }
+        }
+        Ok(())
+    }
 
-        let signer = Signer::<T, T::AuthorityId>::any_account();
-        if !signer.can_sign() {
-            debug::error!("No local account available");
-            return Err(<Error<T>>::Other);
+    /// Reports once that a queued transaction expired on Iroha.
+    fn report_iroha_tx_expired(iroha_tx_id: IrohaTxId, hash: &T::Hash) -> Result<(), Error<T>> {
+        let key = format!(
+            "iroha-bridge-ocw::iroha-tx-expired-{}",
+            utils::hex_hash(hash)
+        );
+        let s_reported = StorageValueRef::persistent(key.as_bytes());
+        if s_reported.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
         }
+        debug::info!("Iroha transaction {} expired", iroha_tx_id);
+        Self::send_signed(Call::iroha_tx_expired(iroha_tx_id))?;
+        s_reported.set(&true);
+        Ok(())
+    }
 
-        let result = signer.send_signed_transaction(|_acc| {
-            Call::outgoing_transfer(
-                from_account_id.clone(),
-                to_account_id.clone(),
-                asset_kind,
-                amount,
-                nonce,
-            )
-        });
+    /// Reports once that a queued transaction is in a committed Iroha block.
+    fn report_iroha_tx_committed(iroha_tx_id: IrohaTxId) -> Result<(), Error<T>> {
+        let key = format!("iroha-bridge-ocw::iroha-tx-committed-{}", iroha_tx_id);
+        let s_reported = StorageValueRef::persistent(key.as_bytes());
+        if s_reported.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
+        }
+        debug::info!("Iroha transaction {} is committed", iroha_tx_id);
+        Self::send_signed(Call::iroha_tx_committed(iroha_tx_id))?;
+        s_reported.set(&true);
+        Ok(())
+    }
 
-        match result {
-            Some((_acc, Ok(_))) => {
-                let asset_definition_id = asset_kind.definition_id();
-                let bridge_def_id = BridgeDefinitionId::new("polkadot");
-                let quantity = u32::try_from(amount).map_err(|_| <Error<T>>::InvalidBalanceType)?;
+    /// Mints or releases an agreed incoming transfer and queues its acknowledgement to Iroha.
+    /// Returns whether the transfer was credited.
+    fn credit_incoming_transfer(
+        iroha_tx_hash: T::Hash,
+        isi_index: u32,
+        sender: iroha::AccountId,
+        receiver: T::AccountId,
+        asset_id: AssetId,
+        quantity: u128,
+    ) -> bool {
+        let credited =
+            <treasury::Module<T>>::from_iroha_quantity(asset_id, quantity).and_then(|amount| {
+                let definition_id = <treasury::Module<T>>::definition_id(asset_id)?;
+                match <treasury::Module<T>>::asset_mode(asset_id)? {
+                    AssetMode::MintBurn => {
+                        <treasury::Module<T>>::mint(receiver.clone(), asset_id, amount)?
+                    }
+                    AssetMode::LockRelease => {
+                        <treasury::Module<T>>::release(receiver.clone(), asset_id, amount)?
+                    }
+                }
+                Ok((amount, definition_id))
+            });
+        let (amount, definition_id) = match credited {
+            Ok(credited) => credited,
+            Err(e) => {
+                debug::warn!(
+                    "Rejected incoming transfer {:?} #{} of {} {:?}: {:?}",
+                    iroha_tx_hash,
+                    isi_index,
+                    quantity,
+                    asset_id,
+                    e
+                );
+                Self::deposit_event(RawEvent::IncomingTransferRejected(iroha_tx_hash, isi_index));
+                return false;
+            }
+        };
+        debug::info!(
+            "Incoming transfer from {} to {:?} with {:?} {:?}",
+            sender,
+            receiver,
+            amount,
+            asset_id
+        );
+        // acknowledged to Iroha with a transaction created now, so that the authorities
+        // sign the same one however late they process the Iroha block
+        Self::queue_iroha_tx(
+            IrohaTxPurpose::IncomingTransfer(iroha_tx_hash, isi_index),
+            vec![Self::incoming_transfer_ack(
+                iroha_tx_hash,
+                isi_index,
+                &receiver,
+                definition_id,
+                quantity as u32,
+            )],
+        );
+        Self::deposit_event(RawEvent::IncomingTransfer(
+            sender, receiver, asset_id, amount,
+        ));
+        true
+    }
+
+    /// Adds the vote of `author` to send an incoming transfer back to its Iroha sender. With
+    /// `Threshold` votes for the same refund its Iroha transaction is queued.
+    fn vote_incoming_transfer_refund(
+        author: T::AccountId,
+        iroha_tx_hash: T::Hash,
+        isi_index: u32,
+        sender: iroha::AccountId,
+        definition_id: iroha::AssetDefinitionId,
+        quantity: u32,
+    ) -> DispatchResult {
+        let key = (iroha_tx_hash, isi_index);
+        ensure!(
+            !<ProcessedIrohaTxs<T>>::get(key),
+            <Error<T>>::IrohaTxAlreadyProcessed
+        );
+        let refund = (iroha_tx_hash, isi_index, sender, definition_id, quantity);
+        let mut votes = <IncomingRefundVotes<T>>::get(&refund);
+        ensure!(!votes.contains(&author), <Error<T>>::AlreadyVotedForRefund);
 
-                let instructions = vec![bridge::isi::handle_incoming_transfer(
+        votes.push(author);
+        if !Self::is_threshold_reached(votes.len()) {
+            <IncomingRefundVotes<T>>::insert(&refund, votes);
+            return Ok(());
+        }
+        <IncomingRefundVotes<T>>::remove(&refund);
+        <ProcessedIrohaTxs<T>>::insert(key, true);
+        let (_, _, sender, definition_id, quantity) = refund;
+        let bridge_account_id = Self::bridge_account_id();
+        let asset = iroha::Asset::with_quantity(
+            iroha::AssetId::new(definition_id, bridge_account_id.clone()),
+            quantity,
+        );
+        let instruction = AccountInstruction::TransferAsset(bridge_account_id, sender, asset);
+        Self::queue_iroha_tx(
+            IrohaTxPurpose::IncomingTransferRefund(iroha_tx_hash, isi_index),
+            vec![iroha::Instruction::Account(instruction)],
+        );
+        Self::deposit_event(RawEvent::IncomingTransferRefunded(iroha_tx_hash, isi_index));
+        Ok(())
+    }
+
+    /// Builds the Iroha instruction registering an incoming transfer credited to `receiver` with
+    /// the bridge definition.
+    fn incoming_transfer_ack(
+        tx_hash: T::Hash,
+        isi_index: u32,
+        receiver: &T::AccountId,
+        definition_id: iroha::AssetDefinitionId,
+        quantity: u32,
+    ) -> iroha::Instruction {
+        let tx = ExternalTransaction {
+            hash: utils::hex_hash(&tx_hash),
+            payload: (isi_index, receiver).encode(),
+        };
+        bridge::isi::handle_outgoing_transfer(
+            &Self::bridge_definition_id(),
+            &definition_id,
+            quantity,
+            0,
+            &tx,
+        )
+    }
+
+    /// Builds the Iroha instructions registering an outgoing transfer with the bridge definition.
+    fn outgoing_instructions(
+        request_id: RequestId,
+        transfer: &OutgoingTransfer<T::AccountId, T::BlockNumber>,
+    ) -> Result<Vec<iroha::Instruction>, Error<T>> {
+        let asset_definition_id = <treasury::Module<T>>::definition_id(transfer.asset_id)
+            .map_err(|_| <Error<T>>::UnknownAsset)?;
+        let bridge_def_id = Self::bridge_definition_id();
+        let quantity = <treasury::Module<T>>::to_iroha_quantity(transfer.asset_id, transfer.amount)
+            .map_err(|_| <Error<T>>::InvalidBalanceType)?;
+        let external_tx = Self::outgoing_external_tx(request_id, transfer);
+
+        Ok(utils::split_quantity(quantity)
+            .into_iter()
+            .map(|part| {
+                bridge::isi::handle_incoming_transfer(
                     &bridge_def_id,
                     &asset_definition_id,
-                    quantity,
+                    part,
                     0,
-                    to_account_id.clone(),
-                    &ExternalTransaction {
-                        hash: "".into(),
-                        payload: vec![],
-                    },
-                )];
-                let resp = Self::send_instructions(instructions);
-                if resp.is_err() {
-                    debug::error!("Error while sending instructions");
-                    return Err(<Error<T>>::SubmitInstructionsFailed);
-                }
-            }
+                    transfer.receiver.clone(),
+                    &external_tx,
+                )
+            })
+            .collect())
+    }
+
+    /// Links an outgoing transfer to the Substrate extrinsic that requested it. The hash is the
+    /// hash of the requesting block, the payload is the extrinsic index followed by the request.
+    /// The sender and nonce in the request let the Iroha side deduplicate it.
+    fn outgoing_external_tx(
+        request_id: RequestId,
+        transfer: &OutgoingTransfer<T::AccountId, T::BlockNumber>,
+    ) -> ExternalTransaction {
+        let block_hash = <system::Module<T>>::block_hash(transfer.created_at);
+        ExternalTransaction {
+            hash: utils::hex_hash(&block_hash),
+            payload: (
+                transfer.extrinsic_index,
+                request_id,
+                &transfer.sender,
+                &transfer.receiver,
+                transfer.asset_id,
+                transfer.amount,
+                transfer.nonce,
+            )
+                .encode(),
+        }
+    }
+
+    /// Votes once to refund an outgoing transfer that could not be sent to Iroha.
+    fn refund_outgoing_transfer_ocw(request_id: RequestId, reason: &str) -> Result<(), Error<T>> {
+        let key = format!("iroha-bridge-ocw::outgoing-refunded-{}", request_id);
+        let s_refunded = StorageValueRef::persistent(key.as_bytes());
+        if s_refunded.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
+        }
+        debug::info!("Refunding outgoing transfer request {}", request_id);
+        Self::send_signed(Call::refund_outgoing_transfer(
+            request_id,
+            reason.as_bytes().to_vec(),
+        ))?;
+        s_refunded.set(&true);
+        Ok(())
+    }
+
+    /// Votes once to send a transfer into the bridge account back to its Iroha sender.
+    fn refund_incoming_transfer_ocw(
+        tx_hash: T::Hash,
+        isi_index: u32,
+        from: iroha::AccountId,
+        asset: iroha::Asset,
+    ) -> Result<(), Error<T>> {
+        let key = format!(
+            "iroha-bridge-ocw::incoming-refunded-{}-{}",
+            utils::hex_hash(&tx_hash),
+            isi_index
+        );
+        let s_refunded = StorageValueRef::persistent(key.as_bytes());
+        if s_refunded.get::<bool>().flatten().unwrap_or(false) {
+            return Ok(());
+        }
+        Self::send_signed(Call::refund_incoming_transfer(
+            tx_hash,
+            isi_index,
+            from,
+            asset.id.definition_id,
+            asset.quantity,
+        ))?;
+        s_refunded.set(&true);
+        Ok(())
+    }
+
+    /// Submits `call` signed by any local authority account.
+    fn send_signed(call: Call<T>) -> Result<(), Error<T>> {
+        let signer = Signer::<T, T::AuthorityId>::any_account();
+        if !signer.can_sign() {
+            debug::error!("No local account available");
+            return Err(<Error<T>>::Other);
+        }
+
+        match signer.send_signed_transaction(|_acc| call.clone()) {
+            Some((_acc, Ok(_))) => Ok(()),
             Some((acc, Err(e))) => {
-                debug::error!("[{:?}] Failed in signed_submit_number: {:?}", acc.id, e);
-                return Err(<Error<T>>::SendSignedTransactionError);
+                debug::error!("[{:?}] Failed in send_signed: {:?}", acc.id, e);
+                Err(<Error<T>>::SendSignedTransactionError)
             }
             _ => {
-                debug::error!("Failed in signed_submit_number");
-                return Err(<Error<T>>::SendSignedTransactionError);
+                debug::error!("Failed in send_signed");
+                Err(<Error<T>>::SendSignedTransactionError)
             }
         }
-        Ok(())
     }
 
-    fn http_request<B: Encode, R: Decode>(url: &str, body: &B) -> Result<R, Error<T>> {
+    /// Resolves the Iroha peer URL: the node-local override if set, the on-chain default otherwise.
+    fn iroha_url() -> Result<String, Error<T>> {
+        let endpoint = StorageValueRef::persistent(IROHA_ENDPOINT_KEY)
+            .get::<Vec<u8>>()
+            .flatten()
+            .unwrap_or_else(Self::iroha_endpoint);
+        match String::from_utf8(endpoint) {
+            Ok(url) if utils::is_valid_iroha_endpoint(url.as_bytes()) => {
+                Ok(url.trim_end_matches('/').into())
+            }
+            _ => {
+                debug::error!("Iroha endpoint is not set or is not a valid URL");
+                Err(<Error<T>>::InvalidIrohaEndpoint)
+            }
+        }
+    }
+
+    fn http_request<B: Encode, R: Decode>(path: &str, body: &B) -> Result<R, Error<T>> {
+        let url = format!("{}{}", Self::iroha_url()?, path);
         debug::trace!("Sending request to: {}", url);
-        let request = rt_offchain::http::Request::post(url, vec![body.encode()]);
+        let request = rt_offchain::http::Request::post(&url, vec![body.encode()]);
         let timeout = sp_io::offchain::timestamp().add(rt_offchain::Duration::from_millis(10000));
         let pending = request.deadline(timeout).send().map_err(|e| {
             debug::error!("Failed to send a request {:?}", e);
@@ -570,8 +2083,210 @@ impl<T: Trait> Module<T> {
         })
     }
 
+    /// Until a new threshold takes effect, the authorities left after a rotation reach the old
+    /// one if there are fewer of them.
+    fn is_threshold_reached(votes: usize) -> bool {
+        votes >= Self::threshold().min(Self::authority_count()).max(1) as usize
+    }
+
+    /// The threshold once the pending change, if any, takes effect.
+    fn target_threshold() -> u32 {
+        Self::pending_threshold().map_or_else(Self::threshold, |(threshold, _)| threshold)
+    }
+
+    /// The Iroha account the bridge sends transactions as.
+    pub fn bridge_account_id() -> iroha::AccountId {
+        Self::bridge_account().unwrap_or_else(|| iroha::AccountId::new("bridge", "polkadot"))
+    }
+
+    /// The Iroha bridge definition transfers are registered with.
+    pub fn bridge_definition_id() -> BridgeDefinitionId {
+        Self::bridge_definition()
+            .unwrap_or_else(|| BridgeDefinitionId::new(&Self::bridge_account_id().domain_name))
+    }
+
+    /// Queues a bridge account transaction created now.
+    fn queue_iroha_tx(
+        purpose: IrohaTxPurpose<T::Hash>,
+        instructions: Vec<iroha::Instruction>,
+    ) -> IrohaTxId {
+        let iroha_tx_id = Self::next_iroha_tx_id();
+        NextIrohaTxId::put(iroha_tx_id + 1);
+        let tx = Self::bridge_tx(instructions, Self::now_millis());
+        let hash = T::Hashing::hash(&tx.payload.encode());
+        <PendingIrohaTxs<T>>::insert(
+            iroha_tx_id,
+            PendingIrohaTx {
+                purpose,
+                tx,
+                hash,
+                sent: false,
+            },
+        );
+        Self::deposit_event(RawEvent::IrohaTxQueued(iroha_tx_id));
+        iroha_tx_id
+    }
+
+    /// Burns the funds of an outgoing transfer committed on Iroha, or keeps them in escrow.
+    fn finalize_outgoing_transfer(request_id: RequestId) -> DispatchResult {
+        let transfer = <PendingOutgoing<T>>::get(request_id).ok_or(<Error<T>>::UnknownRequest)?;
+        ensure!(
+            transfer.status == OutgoingTransferStatus::Approved,
+            <Error<T>>::InvalidRequestStatus
+        );
+        let OutgoingTransfer {
+            sender,
+            receiver,
+            asset_id,
+            amount,
+            ..
+        } = transfer;
+        match <treasury::Module<T>>::asset_mode(asset_id)? {
+            AssetMode::MintBurn => {
+                <treasury::Module<T>>::burn(sender.clone(), asset_id, amount, Some(request_id))?
+            }
+            AssetMode::LockRelease => {
+                <treasury::Module<T>>::escrow(sender.clone(), asset_id, amount, Some(request_id))?
+            }
+        }
+        <PendingOutgoing<T>>::remove(request_id);
+        <OutgoingRefundVotes<T>>::remove(request_id);
+        debug::info!(
+            "Finalized outgoing transfer request {} {:?} from {:?} to {}",
+            amount,
+            asset_id,
+            sender,
+            receiver
+        );
+        Self::deposit_event(RawEvent::OutgoingTransfer(
+            request_id, sender, receiver, asset_id, amount,
+        ));
+        Ok(())
+    }
+
+    /// Current chain time in unix milliseconds.
+    fn now_millis() -> u64 {
+        <pallet_timestamp::Module<T>>::get().unique_saturated_into()
+    }
+
     fn is_authority(who: &T::AccountId) -> bool {
-        Self::authorities().into_iter().find(|i| i == who).is_some()
+        <Authorities<T>>::contains_key(who)
+    }
+
+    /// Current authorities.
+    pub fn authorities() -> Vec<T::AccountId> {
+        <Authorities<T>>::iter().map(|(who, _)| who).collect()
+    }
+
+    fn ensure_valid_threshold(authority_count: u32, threshold: u32) -> Result<(), Error<T>> {
+        ensure!(
+            threshold >= 1 && threshold <= authority_count,
+            <Error<T>>::InvalidThreshold
+        );
+        Ok(())
+    }
+
+    fn queue_signatory_update(update: SignatoryUpdate) {
+        let update_id = Self::next_signatory_update_id();
+        NextSignatoryUpdateId::put(update_id + 1);
+        let bridge_account_id = Self::bridge_account_id();
+        let instruction = match update.clone() {
+            SignatoryUpdate::Add(public_key) => {
+                AccountInstruction::AddSignatory(bridge_account_id, public_key)
+            }
+            SignatoryUpdate::Remove(public_key) => {
+                AccountInstruction::RemoveSignatory(bridge_account_id, public_key)
+            }
+        };
+        let iroha_tx_id = Self::queue_iroha_tx(
+            IrohaTxPurpose::SignatoryUpdate(update_id),
+            vec![iroha::Instruction::Account(instruction)],
+        );
+        PendingSignatoryUpdates::insert(update_id, (update, iroha_tx_id));
+        Self::deposit_event(RawEvent::SignatoryUpdateQueued(update_id));
+    }
+
+    /// Makes the bridge account's transactions need `threshold` signatures on Iroha as well.
+    /// `Threshold` follows once the update is committed, so that until then the bridge account's
+    /// transactions are signed by as many authorities as its current quorum needs.
+    fn queue_threshold_update(threshold: u32) {
+        let instruction =
+            AccountInstruction::SetQuorum(Self::bridge_account_id(), threshold.max(1));
+        let iroha_tx_id = Self::queue_iroha_tx(
+            IrohaTxPurpose::QuorumUpdate(threshold),
+            vec![iroha::Instruction::Account(instruction)],
+        );
+        PendingThreshold::put((threshold, iroha_tx_id));
+        Self::deposit_event(RawEvent::ThresholdChangeQueued(threshold));
+    }
+
+    /// Makes the pending threshold `Threshold` once its quorum update and the signatories added
+    /// before it are committed on Iroha.
+    fn apply_pending_threshold() {
+        let (threshold, iroha_tx_id) = match Self::pending_threshold() {
+            Some(pending) => pending,
+            None => return,
+        };
+        let adding =
+            PendingSignatoryUpdates::iter().any(|(_, (update, update_tx_id))| match update {
+                SignatoryUpdate::Add(_) => update_tx_id < iroha_tx_id,
+                SignatoryUpdate::Remove(_) => false,
+            });
+        if adding || <PendingIrohaTxs<T>>::contains_key(iroha_tx_id) {
+            return;
+        }
+        Threshold::put(threshold);
+        PendingThreshold::kill();
+        Self::deposit_event(RawEvent::ThresholdChanged(threshold));
+    }
+
+    /// Signatories of the Iroha bridge account as committed on Iroha: the authorities' keys
+    /// with the pending signatory updates undone.
+    fn committed_signatories() -> BTreeSet<iroha_crypto::PublicKey> {
+        let mut signatories: BTreeSet<_> = <Authorities<T>>::iter().map(|(_, key)| key).collect();
+        let mut updates: Vec<_> = PendingSignatoryUpdates::iter().collect();
+        updates.sort_by_key(|(update_id, _)| *update_id);
+        for (_, (update, _)) in updates.into_iter().rev() {
+            match update {
+                SignatoryUpdate::Add(key) => signatories.remove(&key),
+                SignatoryUpdate::Remove(key) => signatories.insert(key),
+            };
+        }
+        signatories
+    }
+
+    /// Returns the Iroha peer set that was valid at the Iroha block `height`.
+    pub fn iroha_peers_at(height: u64) -> Vec<iroha_crypto::PublicKey> {
+        if height >= Self::iroha_peers_active_from() {
+            return Self::iroha_peers();
+        }
+        Self::previous_iroha_peers()
+            .into_iter()
+            .rev()
+            .find(|(active_from, _)| *active_from <= height)
+            .map(|(_, peers)| peers)
+            .unwrap_or_default()
+    }
+
+    /// Makes `peers` the Iroha peer set from `activation_height` on, keeping the current set for
+    /// the blocks below. Rotating at the height of the current set replaces it.
+    fn rotate_iroha_peers(
+        peers: Vec<iroha_crypto::PublicKey>,
+        activation_height: u64,
+    ) -> DispatchResult {
+        ensure!(!peers.is_empty(), <Error<T>>::EmptyIrohaPeerSet);
+        Self::ensure_valid_iroha_peers_threshold(Self::iroha_peers_threshold(), peers.len())?;
+        let active_from = Self::iroha_peers_active_from();
+        ensure!(
+            activation_height >= active_from,
+            <Error<T>>::InvalidActivationHeight
+        );
+        if activation_height > active_from {
+            PreviousIrohaPeers::mutate(|v| v.push((active_from, Self::iroha_peers())));
+            IrohaPeersActiveFrom::put(activation_height);
+        }
+        IrohaPeers::put(peers);
+        Ok(())
     }
 
     fn is_iroha_peer(peer: &iroha_crypto::PublicKey) -> bool {
@@ -580,4 +2295,18 @@ impl<T: Trait> Module<T> {
             .find(|i| i == peer)
             .is_some()
     }
+
+    /// An explicit Iroha block signature threshold has to be reachable by the peer set.
+    fn ensure_valid_iroha_peers_threshold(
+        threshold: Option<u32>,
+        peer_count: usize,
+    ) -> Result<(), Error<T>> {
+        if let Some(threshold) = threshold {
+            ensure!(
+                threshold >= 1 && threshold as usize <= peer_count,
+                <Error<T>>::InvalidIrohaPeersThreshold
+            );
+        }
+        Ok(())
+    }
 }
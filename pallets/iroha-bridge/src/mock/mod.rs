@@ -2,7 +2,7 @@
 
 use crate as iroha_bridge;
 use frame_support::dispatch::{DispatchInfo, GetDispatchInfo};
-use frame_support::traits::StorageMapShim;
+use frame_support::traits::Get;
 use frame_support::weights::Pays;
 use frame_support::{construct_runtime, parameter_types, weights::Weight};
 use frame_system as system;
@@ -23,6 +23,7 @@ use sp_runtime::{
     AccountId32, ApplyExtrinsicResultWithInfo, MultiSignature, Perbill,
 };
 use sp_std::fmt::Debug;
+use std::cell::RefCell;
 pub mod offchain_testing;
 pub use offchain_testing::*;
 
@@ -195,11 +196,30 @@ impl system::Trait for Test {
     type OnKilledAccount = ();
 }
 
+parameter_types! {
+    pub const NativeAssetId: treasury::AssetId = NATIVE;
+}
+
 impl treasury::Trait for Test {
+    type Balance = Balance;
+    type Currency = Balances;
+    type NativeAssetId = NativeAssetId;
     type Event = Event;
-    type XOR = pallet_balances::Module<Test, pallet_balances::Instance1>;
-    type DOT = pallet_balances::Module<Test, pallet_balances::Instance2>;
-    type KSM = pallet_balances::Module<Test, pallet_balances::Instance3>;
+}
+
+pub const XOR: treasury::AssetId = 0;
+pub const DOT: treasury::AssetId = 1;
+pub const KSM: treasury::AssetId = 2;
+pub const NATIVE: treasury::AssetId = 4;
+
+parameter_types! {
+    pub const MinimumPeriod: u64 = 1;
+}
+
+impl pallet_timestamp::Trait for Test {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = MinimumPeriod;
 }
 
 impl<T: SigningTypes> system::offchain::SignMessage<T> for Test {
@@ -258,59 +278,12 @@ impl pallet_balances::Trait for Test {
     type AccountStore = System;
 }
 
-/// XOR
-impl pallet_balances::Trait<pallet_balances::Instance1> for Test {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = Event; //pallet_balances::Event<Test, pallet_balances::Instance1>;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        pallet_balances::Account<Test, pallet_balances::Instance1>,
-        system::CallOnCreatedAccount<Test>,
-        system::CallKillAccount<Test>,
-        AccountId,
-        pallet_balances::AccountData<Balance>,
-    >;
-}
-
-/// DOT
-impl pallet_balances::Trait<pallet_balances::Instance2> for Test {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = pallet_balances::Event<Test, pallet_balances::Instance2>;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        pallet_balances::Account<Test, pallet_balances::Instance1>,
-        system::CallOnCreatedAccount<Test>,
-        system::CallKillAccount<Test>,
-        AccountId,
-        pallet_balances::AccountData<Balance>,
-    >;
-}
-
-/// KSM
-impl pallet_balances::Trait<pallet_balances::Instance3> for Test {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = Event;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        pallet_balances::Account<Test, pallet_balances::Instance1>,
-        system::CallOnCreatedAccount<Test>,
-        system::CallKillAccount<Test>,
-        AccountId,
-        pallet_balances::AccountData<Balance>,
-    >;
-}
-
 parameter_types! {
     pub const UnsignedPriority: u64 = 100;
+    pub const OutgoingTransferTimeout: BlockNumber = 10;
+    pub const MaxOutgoingTransferRetries: u32 = 2;
+    pub const IncomingAttestationTimeout: BlockNumber = 5;
+    pub const Ss58Prefix: u8 = 42;
 }
 
 impl iroha_bridge::Trait for Test {
@@ -319,6 +292,30 @@ impl iroha_bridge::Trait for Test {
     type Call = Call;
     type Event = Event;
     type UnsignedPriority = UnsignedPriority;
+    type OutgoingTransferTimeout = OutgoingTransferTimeout;
+    type MaxOutgoingTransferRetries = MaxOutgoingTransferRetries;
+    type IncomingAttestationTimeout = IncomingAttestationTimeout;
+    type OversizedTransferPolicy = OversizedTransfers;
+    type Ss58Prefix = Ss58Prefix;
+}
+
+thread_local! {
+    static OVERSIZED_TRANSFER_POLICY: RefCell<iroha_bridge::OversizedTransferPolicy> =
+        RefCell::new(iroha_bridge::OversizedTransferPolicy::Split);
+}
+
+pub struct OversizedTransfers;
+
+impl OversizedTransfers {
+    pub fn set(policy: iroha_bridge::OversizedTransferPolicy) {
+        OVERSIZED_TRANSFER_POLICY.with(|v| *v.borrow_mut() = policy);
+    }
+}
+
+impl Get<iroha_bridge::OversizedTransferPolicy> for OversizedTransfers {
+    fn get() -> iroha_bridge::OversizedTransferPolicy {
+        OVERSIZED_TRANSFER_POLICY.with(|v| *v.borrow())
+    }
 }
 
 impl sp_runtime::traits::ExtrinsicMetadata for TestExtrinsic {
@@ -334,11 +331,9 @@ construct_runtime!(
         UncheckedExtrinsic = TestExtrinsic
     {
         System: system::{Module, Call, Config, Storage, Event<T>},
-        Treasury: treasury::{Module, Call, Storage, Event<T>},
+        Treasury: treasury::{Module, Call, Storage, Config<T>, Event<T>},
         Balances: pallet_balances::{Module, Call, Storage, Config<T>, Event<T>},
-        XOR: pallet_balances::<Instance1>::{Module, Call, Storage, Config<T>, Event<T>},
-        DOT: pallet_balances::<Instance2>::{Module, Call, Storage, Config<T>, Event<T>},
-        KSM: pallet_balances::<Instance3>::{Module, Call, Storage, Config<T>, Event<T>},
+        Timestamp: pallet_timestamp::{Module, Call, Storage, Inherent},
         IrohaBridge: iroha_bridge::{Module, Call, Storage, Config<T>, Event<T>},
     }
 );
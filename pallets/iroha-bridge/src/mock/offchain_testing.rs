@@ -26,7 +26,10 @@ use sp_core::offchain::{
     HttpError, HttpRequestId as RequestId, HttpRequestStatus as RequestStatus, OffchainStorage,
     OpaqueNetworkState, StorageKind, Timestamp, TransactionPool,
 };
-use std::{collections::BTreeMap, sync::Arc};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::Arc,
+};
 
 use parity_scale_codec::alloc::collections::{HashMap, HashSet};
 use parking_lot::RwLock;
@@ -48,6 +51,8 @@ pub struct PendingRequest {
     pub sent: bool,
     /// Response body
     pub response: Option<Vec<u8>>,
+    /// Response status code, `200` if not set.
+    pub response_code: Option<u16>,
     /// Number of bytes already read from the response body.
     pub read: usize,
     /// Response headers
@@ -123,6 +128,10 @@ pub struct OffchainState {
     pub seed: [u8; 32],
     /// A timestamp simulating the current time.
     pub timestamp: Timestamp,
+    /// If set, every new request is answered right away with an empty body and this status code.
+    pub http_failure_code: Option<u16>,
+    /// Bodies answering new requests right away with status `200`, in order.
+    pub http_responses: VecDeque<Vec<u8>>,
 }
 
 impl OffchainState {
@@ -246,12 +255,19 @@ impl offchain::Externalities for TestOffchainExt {
     ) -> Result<RequestId, ()> {
         let mut state = self.0.write();
         let id = RequestId(state.requests.len() as u16);
+        let failure_code = state.http_failure_code;
+        let response = match failure_code {
+            Some(_) => Some(vec![]),
+            None => state.http_responses.pop_front(),
+        };
         state.requests.insert(
             id.clone(),
             PendingRequest {
                 method: method.into(),
                 uri: uri.into(),
                 meta: meta.into(),
+                response,
+                response_code: failure_code,
                 ..Default::default()
             },
         );
@@ -313,9 +329,12 @@ impl offchain::Externalities for TestOffchainExt {
                                 pending_ids.remove(id);
                                 statuses.insert(id, RequestStatus::Invalid);
                             }
-                            _ => {
+                            Some(req) => {
                                 pending_ids.remove(id);
-                                statuses.insert(id, RequestStatus::Finished(200));
+                                statuses.insert(
+                                    id,
+                                    RequestStatus::Finished(req.response_code.unwrap_or(200)),
+                                );
                             }
                         }
                     }
@@ -369,7 +388,7 @@ impl offchain::Externalities for TestOffchainExt {
                 Ok(0)
             } else {
                 let read = std::cmp::min(buffer.len(), response[req.read..].len());
-                buffer[0..read].copy_from_slice(&response[req.read..read]);
+                buffer[0..read].copy_from_slice(&response[req.read..req.read + read]);
                 req.read += read;
                 Ok(read)
             }
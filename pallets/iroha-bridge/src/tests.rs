@@ -1,9 +1,17 @@
-use crate::{mock::*, KEY_TYPE, KEY_TYPE_2};
-use frame_support::{assert_ok, traits::OnInitialize};
-use sp_core::{crypto::AccountId32, sr25519, Pair, Public};
+use crate::{mock::*, Error, KEY_TYPE, KEY_TYPE_2};
+use frame_support::{
+    assert_err, assert_ok,
+    storage::{StorageMap, StorageValue},
+    traits::{Get, OnInitialize},
+};
+use sp_core::{
+    crypto::{AccountId32, Ss58Codec},
+    ed25519, sr25519, Pair, Public,
+};
 use sp_runtime::{
-    traits::{Dispatchable, IdentifyAccount, Verify},
-    MultiSignature as Signature,
+    offchain::storage::StorageValueRef,
+    traits::{BlakeTwo256, Dispatchable, Hash, IdentifyAccount, Verify},
+    DispatchError, MultiSignature as Signature,
 };
 
 use async_std::task;
@@ -11,10 +19,16 @@ use async_std::task;
 use iroha::{config::Configuration, prelude};
 use iroha_client::client::account::by_id;
 use iroha_client::{client::Client, config::Configuration as ClientConfiguration};
-use iroha_client_no_std::crypto as iroha_crypto;
+use iroha_client_no_std::account::isi::AccountInstruction;
+use iroha_client_no_std::asset::isi::AssetInstruction;
+use iroha_client_no_std::block::{BlockHeader, Message as BlockMessage, ValidBlock};
+use iroha_client_no_std::bridge::{self, BridgeDefinitionId};
+use iroha_client_no_std::crypto::{self as iroha_crypto, Signatures};
+use iroha_client_no_std::peer::PeerId;
 use iroha_client_no_std::prelude as no_std_prelude;
+use iroha_client_no_std::tx::{RequestedTransaction, ValidTransaction};
 use parity_scale_codec::alloc::sync::Arc;
-use parity_scale_codec::Decode;
+use parity_scale_codec::{Decode, Encode};
 use parking_lot::RwLock;
 use sp_core::{
     offchain::{OffchainExt, TransactionPoolExt},
@@ -22,13 +36,13 @@ use sp_core::{
     traits::KeystoreExt,
 };
 use sp_io::TestExternalities;
+use std::collections::BTreeSet;
 use std::thread;
 use tempfile::TempDir;
 
-use sp_core::offchain::Timestamp;
+use sp_core::offchain::Timestamp as OffchainTimestamp;
 
 use frame_support::sp_std::convert::TryFrom;
-use treasury::AssetKind;
 
 pub type SubstrateAccountId = <<Signature as Verify>::Signer as IdentifyAccount>::AccountId;
 
@@ -63,32 +77,17 @@ impl ExtBuilder {
 
         let storage = GenesisConfig {
             system: Some(frame_system::GenesisConfig::default()),
-            pallet_balances_Instance1: Some(XORConfig {
-                balances: endowed_accounts
-                    .iter()
-                    .cloned()
-                    .filter(|x| {
-                        x != &AccountId32::from([
-                            52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206,
-                            243, 67, 8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81,
-                            120,
-                        ])
-                    })
-                    .map(|k| (k, 0))
-                    .collect(),
-            }),
-            pallet_balances_Instance2: Some(DOTConfig {
+            treasury: Some(TreasuryConfig {
+                assets: vec![
+                    (XOR, b"XOR".to_vec(), b"global".to_vec(), 0, 0),
+                    (DOT, b"DOT".to_vec(), b"polkadot".to_vec(), 0, 0),
+                    (KSM, b"KSM".to_vec(), b"polkadot".to_vec(), 0, 0),
+                    (NATIVE, b"UNIT".to_vec(), b"polkadot".to_vec(), 0, 0),
+                ],
                 balances: endowed_accounts
                     .iter()
                     .cloned()
-                    .map(|k| (k, 1 << 8))
-                    .collect(),
-            }),
-            pallet_balances_Instance3: Some(KSMConfig {
-                balances: endowed_accounts
-                    .iter()
-                    .cloned()
-                    .map(|k| (k, 1 << 8))
+                    .flat_map(|k| vec![(DOT, k.clone(), 1 << 8), (KSM, k, 1 << 8)])
                     .collect(),
             }),
             pallet_balances: Some(BalancesConfig {
@@ -100,12 +99,19 @@ impl ExtBuilder {
             }),
             // pallet_sudo: Some(SudoConfig { key: root_key }),
             iroha_bridge: Some(IrohaBridgeConfig {
-                authorities: endowed_accounts.clone(),
+                authorities: vec![
+                    (endowed_accounts[0].clone(), authority_iroha_key("Alice")),
+                    (endowed_accounts[1].clone(), authority_iroha_key("Bob")),
+                ],
                 iroha_peers: vec![iroha_crypto::PublicKey::try_from(vec![
                     52u8, 45, 84, 67, 137, 84, 47, 252, 35, 59, 237, 44, 144, 70, 71, 206, 243, 67,
                     8, 115, 247, 189, 204, 26, 181, 226, 232, 81, 123, 12, 81, 120,
                 ])
                 .unwrap()],
+                iroha_endpoint: b"http://127.0.0.1:7878".to_vec(),
+                threshold: 1,
+                bridge_account: None,
+                bridge_definition: None,
             }),
         }
         .build_storage()
@@ -162,9 +168,10 @@ fn create_and_start_iroha() {
 /// non-runtime logic (transaction queue) which needs to mocked inside a runtime test.
 fn seal_block(n: u64, state: Arc<RwLock<PoolState>>, _oc_state: Arc<RwLock<OffchainState>>) {
     assert_eq!(System::block_number(), n);
-    System::set_block_number(n + 1);
     IrohaBridge::offchain();
 
+    System::set_block_number(n + 1);
+    IrohaBridge::on_initialize(System::block_number());
     let transactions = &mut state.write().transactions;
     while let Some(t) = transactions.pop() {
         let e: TestExtrinsic = Decode::decode(&mut &*t).unwrap();
@@ -174,7 +181,6 @@ fn seal_block(n: u64, state: Arc<RwLock<PoolState>>, _oc_state: Arc<RwLock<Offch
         // about validation etc.
         let _ = call.dispatch(Some(who).into()).unwrap();
     }
-    IrohaBridge::on_initialize(System::block_number());
 }
 
 fn offchain_worker_loop(oc_state: Arc<RwLock<OffchainState>>) {
@@ -191,7 +197,7 @@ fn offchain_worker_loop(oc_state: Arc<RwLock<OffchainState>>) {
                     // I <3 tokio
                     {
                         let mut guard = oc_state.write();
-                        guard.timestamp = Timestamp::from_unix_millis(
+                        guard.timestamp = OffchainTimestamp::from_unix_millis(
                             std::time::SystemTime::now()
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap()
@@ -310,16 +316,14 @@ async fn should_transfer_asset_between_iroha_and_substrate() {
     thread::spawn(|| offchain_worker_loop(oc_state_clone));
     ext.execute_with(|| {
         let substrate_balance =
-            Treasury::get_balance_from_account(substrate_user_account.clone(), AssetKind::XOR)
-                .unwrap();
+            Treasury::get_balance_from_account(substrate_user_account.clone(), XOR).unwrap();
         assert_eq!(substrate_balance, 0);
 
         seal_block(1, state.clone(), oc_state.clone());
         seal_block(2, state.clone(), oc_state.clone());
 
         let substrate_balance =
-            Treasury::get_balance_from_account(substrate_user_account.clone(), AssetKind::XOR)
-                .unwrap();
+            Treasury::get_balance_from_account(substrate_user_account.clone(), XOR).unwrap();
         assert_eq!(substrate_balance, 100);
     });
 
@@ -339,11 +343,11 @@ async fn should_transfer_asset_between_iroha_and_substrate() {
 
     ext.execute_with(|| {
         let amount = 100u128;
-        let nonce = 0u8;
+        let nonce = 0u64;
         assert_ok!(IrohaBridge::request_transfer(
             Some(substrate_user_account.clone()).into(),
             no_std_user_account_id.clone(),
-            AssetKind::XOR,
+            XOR,
             amount,
             nonce
         ));
@@ -352,8 +356,7 @@ async fn should_transfer_asset_between_iroha_and_substrate() {
         seal_block(4, state.clone(), oc_state.clone());
 
         let substrate_balance =
-            Treasury::get_balance_from_account(substrate_user_account.clone(), AssetKind::XOR)
-                .unwrap();
+            Treasury::get_balance_from_account(substrate_user_account.clone(), XOR).unwrap();
         assert_eq!(substrate_balance, 0);
     });
     thread::sleep(std::time::Duration::from_secs(10));
@@ -365,3 +368,2346 @@ async fn should_transfer_asset_between_iroha_and_substrate() {
         .expect("Failed to send request.");
     check_response_assets(&response, 100);
 }
+
+fn iroha_signature(pair: &ed25519::Pair, message: &[u8]) -> iroha_crypto::Signature {
+    iroha_crypto::Signature {
+        public_key: iroha_crypto::PublicKey::try_from(pair.public().0.to_vec()).unwrap(),
+        signature: pair.sign(message).0,
+    }
+}
+
+fn iroha_public_key(pair: &ed25519::Pair) -> iroha_crypto::PublicKey {
+    iroha_crypto::PublicKey::try_from(pair.public().0.to_vec()).unwrap()
+}
+
+fn authority_iroha_key(seed: &str) -> iroha_crypto::PublicKey {
+    iroha_public_key(&ed25519::Pair::from_string(&format!("//{}", seed), None).unwrap())
+}
+
+fn signed_block(height: u64, signers: &[&ed25519::Pair]) -> ValidBlock {
+    signed_child_block(height, [0u8; 32], signers)
+}
+
+fn signed_child_block(
+    height: u64,
+    previous_block_hash: [u8; 32],
+    signers: &[&ed25519::Pair],
+) -> ValidBlock {
+    committed_block(height, previous_block_hash, 0, vec![], signers)
+}
+
+/// Iroha block created at `timestamp` committing `transactions`.
+fn committed_block(
+    height: u64,
+    previous_block_hash: [u8; 32],
+    timestamp: u64,
+    transactions: Vec<RequestedTransaction>,
+    signers: &[&ed25519::Pair],
+) -> ValidBlock {
+    let mut block = ValidBlock {
+        header: BlockHeader {
+            timestamp: timestamp.into(),
+            height,
+            previous_block_hash,
+            merkle_root_hash: [0u8; 32],
+            number_of_view_changes: 0,
+            invalidated_blocks_hashes: vec![],
+        },
+        transactions: transactions
+            .into_iter()
+            .map(|tx| ValidTransaction {
+                payload: tx.payload,
+                signatures: tx.signatures,
+            })
+            .collect(),
+        signatures: Signatures::default(),
+    };
+    let block_hash = BlakeTwo256::hash(&block.header.encode());
+    for pair in signers {
+        block
+            .signatures
+            .add(iroha_signature(pair, block_hash.as_ref()));
+    }
+    block
+}
+
+fn iroha_transfer(
+    from: &no_std_prelude::AccountId,
+    to: &no_std_prelude::AccountId,
+    definition_id: no_std_prelude::AssetDefinitionId,
+    quantity: u32,
+) -> no_std_prelude::Instruction {
+    no_std_prelude::Instruction::Account(AccountInstruction::TransferAsset(
+        from.clone(),
+        to.clone(),
+        no_std_prelude::Asset::with_quantity(
+            no_std_prelude::AssetId::new(definition_id, from.clone()),
+            quantity,
+        ),
+    ))
+}
+
+fn peer_pairs(n: usize) -> Vec<ed25519::Pair> {
+    (0..n)
+        .map(|i| ed25519::Pair::from_string(&format!("//Peer{}", i), None).unwrap())
+        .collect()
+}
+
+#[test]
+fn bft_threshold_is_n_minus_f() {
+    assert_eq!(crate::utils::bft_threshold(0), 1);
+    assert_eq!(crate::utils::bft_threshold(1), 1);
+    assert_eq!(crate::utils::bft_threshold(4), 3);
+    assert_eq!(crate::utils::bft_threshold(5), 4);
+    assert_eq!(crate::utils::bft_threshold(6), 5);
+    assert_eq!(crate::utils::bft_threshold(7), 5);
+}
+
+#[test]
+fn should_reject_block_signed_by_a_minority_of_five_peers() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(5);
+        let peers: BTreeSet<_> = pairs.iter().map(iroha_public_key).collect();
+        let threshold = crate::utils::bft_threshold(peers.len());
+        // two blocks signed by disjoint majorities would share a single, possibly faulty, peer
+        let block = signed_block(1, &[&pairs[0], &pairs[1], &pairs[2]]);
+        assert_err!(
+            IrohaBridge::verify_block(&block, &peers, threshold),
+            Error::<Test>::NotEnoughBlockSignatures
+        );
+        let block = signed_block(1, &[&pairs[0], &pairs[1], &pairs[2], &pairs[3]]);
+        assert_ok!(IrohaBridge::verify_block(&block, &peers, threshold));
+    });
+}
+
+#[test]
+fn should_accept_consecutive_blocks_signed_by_the_same_quorum() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(4);
+        let peers: BTreeSet<_> = pairs.iter().map(iroha_public_key).collect();
+        for height in 1..=3 {
+            let block = signed_block(height, &[&pairs[0], &pairs[1], &pairs[2]]);
+            assert_ok!(IrohaBridge::verify_block(&block, &peers, 3));
+        }
+    });
+}
+
+#[test]
+fn should_reject_block_below_threshold() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(4);
+        let peers: BTreeSet<_> = pairs.iter().map(iroha_public_key).collect();
+        let block = signed_block(1, &[&pairs[0], &pairs[1]]);
+        assert_err!(
+            IrohaBridge::verify_block(&block, &peers, 3),
+            Error::<Test>::NotEnoughBlockSignatures
+        );
+    });
+}
+
+#[test]
+fn should_reject_block_signed_by_unknown_peer() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(5);
+        let peers: BTreeSet<_> = pairs[..4].iter().map(iroha_public_key).collect();
+        let block = signed_block(1, &[&pairs[0], &pairs[1], &pairs[2], &pairs[4]]);
+        assert_err!(
+            IrohaBridge::verify_block(&block, &peers, 3),
+            Error::<Test>::InvalidBlockSignature
+        );
+    });
+}
+
+#[test]
+fn should_reject_block_with_forged_signature() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(4);
+        let peers: BTreeSet<_> = pairs.iter().map(iroha_public_key).collect();
+        let mut block = signed_block(1, &[&pairs[0], &pairs[1]]);
+        // a known peer key paired with a signature over a different message
+        block
+            .signatures
+            .add(iroha_signature(&pairs[2], b"not a block hash"));
+        assert_err!(
+            IrohaBridge::verify_block(&block, &peers, 3),
+            Error::<Test>::InvalidBlockSignature
+        );
+    });
+}
+
+fn iroha_block_hash(block: &ValidBlock) -> [u8; 32] {
+    BlakeTwo256::hash(&block.header.encode()).into()
+}
+
+/// Answers the next Iroha block request with `blocks`.
+fn share_blocks(oc_state: &Arc<RwLock<OffchainState>>, blocks: Vec<ValidBlock>) {
+    let pk = iroha_crypto::PublicKey::try_from(vec![0u8; 32]).unwrap();
+    let msg = BlockMessage::ShareBlocks(blocks, PeerId::new("", &pk));
+    oc_state.write().http_responses.push_back(msg.encode());
+}
+
+/// Bridge pallet calls submitted by the offchain worker.
+fn submitted_calls(state: &Arc<RwLock<PoolState>>) -> Vec<crate::Call<Test>> {
+    state
+        .read()
+        .transactions
+        .iter()
+        .map(|t| match TestExtrinsic::decode(&mut &t[..]).unwrap().call {
+            Call::IrohaBridge(call) => call,
+            call => panic!("unexpected call {:?}", call),
+        })
+        .collect()
+}
+
+fn last_fetched_iroha_block() -> Option<u64> {
+    StorageValueRef::persistent(b"iroha-bridge-ocw::last-fetched-height")
+        .get::<u64>()
+        .flatten()
+}
+
+#[test]
+fn should_only_handle_iroha_blocks_extending_the_last_one() {
+    let (mut ext, _, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(1);
+        let peer = &pairs[0];
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(peer)],
+            0
+        ));
+        let block_1 = signed_child_block(1, [0u8; 32], &[peer]);
+        let block_2 = signed_child_block(2, iroha_block_hash(&block_1), &[peer]);
+        share_blocks(&oc_state, vec![block_1.clone(), block_2.clone()]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(last_fetched_iroha_block(), Some(2));
+
+        // block 3 is missing
+        let block_3 = signed_child_block(3, iroha_block_hash(&block_2), &[peer]);
+        let block_4 = signed_child_block(4, iroha_block_hash(&block_3), &[peer]);
+        share_blocks(&oc_state, vec![block_2.clone(), block_4]);
+        assert_err!(IrohaBridge::fetch_iroha(), Error::<Test>::IrohaBlockGap);
+        assert_eq!(last_fetched_iroha_block(), Some(2));
+
+        // another block at the height of the last processed one
+        let mut forked_block_2 = signed_child_block(2, iroha_block_hash(&block_1), &[]);
+        forked_block_2.header.timestamp = 1;
+        let hash = BlakeTwo256::hash(&forked_block_2.header.encode());
+        forked_block_2
+            .signatures
+            .add(iroha_signature(peer, hash.as_ref()));
+        share_blocks(&oc_state, vec![forked_block_2]);
+        assert_err!(IrohaBridge::fetch_iroha(), Error::<Test>::IrohaForkDetected);
+
+        // the next block does not point at the last processed one
+        let orphan_block_3 = signed_child_block(3, [1u8; 32], &[peer]);
+        share_blocks(&oc_state, vec![block_2.clone(), orphan_block_3]);
+        assert_err!(IrohaBridge::fetch_iroha(), Error::<Test>::IrohaForkDetected);
+        assert_eq!(last_fetched_iroha_block(), Some(2));
+
+        share_blocks(&oc_state, vec![block_2, block_3]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(last_fetched_iroha_block(), Some(3));
+    });
+}
+
+#[test]
+fn should_keep_iroha_peers_threshold_reachable() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(3);
+        let peers: Vec<_> = pairs.iter().map(iroha_public_key).collect();
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            peers.clone(),
+            0
+        ));
+        for threshold in &[0, 4] {
+            assert_err!(
+                IrohaBridge::set_iroha_peers_threshold(Origin::root(), Some(*threshold)),
+                Error::<Test>::InvalidIrohaPeersThreshold
+            );
+        }
+        assert_ok!(IrohaBridge::set_iroha_peers_threshold(
+            Origin::root(),
+            Some(3)
+        ));
+        assert_eq!(IrohaBridge::iroha_peers_threshold(), Some(3));
+
+        // shrinking the peer set below an explicit threshold is rejected
+        assert_err!(
+            IrohaBridge::remove_iroha_peer(Origin::root(), peers[0].clone(), 10),
+            Error::<Test>::InvalidIrohaPeersThreshold
+        );
+        assert_err!(
+            IrohaBridge::set_iroha_peers(Origin::root(), peers[..2].to_vec(), 10),
+            Error::<Test>::InvalidIrohaPeersThreshold
+        );
+        assert_eq!(IrohaBridge::iroha_peers(), peers);
+
+        assert_ok!(IrohaBridge::set_iroha_peers_threshold(Origin::root(), None));
+        assert_ok!(IrohaBridge::remove_iroha_peer(
+            Origin::root(),
+            peers[0].clone(),
+            10
+        ));
+    });
+}
+
+#[test]
+fn should_verify_blocks_against_the_peer_set_valid_at_their_height() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let pairs = peer_pairs(5);
+        let old_peers: Vec<_> = pairs[..4].iter().map(iroha_public_key).collect();
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            old_peers.clone(),
+            0
+        ));
+        // peer 0 is decommissioned from Iroha block 10 on, peer 4 joins from block 20 on
+        assert_ok!(IrohaBridge::remove_iroha_peer(
+            Origin::root(),
+            old_peers[0].clone(),
+            10
+        ));
+        assert_err!(
+            IrohaBridge::remove_iroha_peer(Origin::root(), old_peers[0].clone(), 20),
+            Error::<Test>::UnknownIrohaPeer
+        );
+        assert_err!(
+            IrohaBridge::set_iroha_peers(Origin::root(), old_peers.clone(), 5),
+            Error::<Test>::InvalidActivationHeight
+        );
+        assert_err!(
+            IrohaBridge::set_iroha_peers(
+                Origin::root(),
+                vec![old_peers[1].clone(), old_peers[1].clone()],
+                20
+            ),
+            Error::<Test>::DuplicateIrohaPeer
+        );
+        let new_peers: Vec<_> = pairs[1..].iter().map(iroha_public_key).collect();
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            new_peers.clone(),
+            20
+        ));
+
+        // rotating at the height of the current set replaces it
+        assert_eq!(IrohaBridge::previous_iroha_peers().len(), 2);
+        assert_eq!(IrohaBridge::iroha_peers_at(9), old_peers);
+        assert_eq!(IrohaBridge::iroha_peers_at(10), old_peers[1..].to_vec());
+        assert_eq!(IrohaBridge::iroha_peers_at(25), new_peers);
+
+        let peers_at =
+            |height| -> BTreeSet<_> { IrohaBridge::iroha_peers_at(height).into_iter().collect() };
+        let block = signed_block(9, &[&pairs[0], &pairs[1], &pairs[2]]);
+        assert_ok!(IrohaBridge::verify_block(&block, &peers_at(9), 3));
+        let block = signed_block(10, &[&pairs[0], &pairs[1], &pairs[2]]);
+        assert_err!(
+            IrohaBridge::verify_block(&block, &peers_at(10), 3),
+            Error::<Test>::InvalidBlockSignature
+        );
+        let block = signed_block(20, &[&pairs[2], &pairs[3], &pairs[4]]);
+        assert_ok!(IrohaBridge::verify_block(&block, &peers_at(20), 3));
+
+        let removed =
+            Event::iroha_bridge(crate::RawEvent::IrohaPeerRemoved(old_peers[0].clone(), 10));
+        assert!(System::events().iter().any(|r| r.event == removed));
+        let set = Event::iroha_bridge(crate::RawEvent::IrohaPeersSet(20));
+        assert!(System::events().iter().any(|r| r.event == set));
+    });
+}
+
+#[test]
+fn should_reject_replayed_incoming_transfer() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let authority = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(authority.clone()).into(),
+            iroha_tx_hash,
+            0,
+            sender.clone(),
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            XOR,
+            100
+        ));
+        assert_err!(
+            IrohaBridge::incoming_transfer(
+                Some(authority.clone()).into(),
+                iroha_tx_hash,
+                0,
+                sender.clone(),
+                receiver.clone(),
+                crate::ReceiverSource::Signatory,
+                XOR,
+                100
+            ),
+            Error::<Test>::IrohaTxAlreadyProcessed
+        );
+        // another instruction of the same transaction is a different transfer
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(authority).into(),
+            iroha_tx_hash,
+            1,
+            sender,
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            XOR,
+            100
+        ));
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver, XOR).unwrap(),
+            200
+        );
+    });
+}
+
+#[test]
+fn should_mint_incoming_transfer_once_threshold_authorities_agree() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+        let attest = |who: &SubstrateAccountId, amount| {
+            IrohaBridge::incoming_transfer(
+                Some(who.clone()).into(),
+                iroha_tx_hash,
+                0,
+                sender.clone(),
+                receiver.clone(),
+                crate::ReceiverSource::Signatory,
+                XOR,
+                amount,
+            )
+        };
+
+        assert_err!(attest(&receiver, 100), Error::<Test>::NotAnAuthority);
+        assert_err!(
+            attest(&alice, u128::from(u32::MAX) + 1),
+            Error::<Test>::AmountTooLarge
+        );
+        assert_ok!(attest(&alice, 100));
+        assert_err!(attest(&alice, 100), Error::<Test>::AlreadyAttested);
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver.clone(), XOR).unwrap(),
+            0
+        );
+
+        assert!(IrohaBridge::pending_iroha_tx(0).is_none());
+
+        Timestamp::set_timestamp(3_000);
+        assert_ok!(attest(&bob, 100));
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver.clone(), XOR).unwrap(),
+            100
+        );
+        assert!(IrohaBridge::processed_iroha_txs((iroha_tx_hash, 0)));
+        assert!(IrohaBridge::incoming_attestations((iroha_tx_hash, 0)).is_empty());
+        // the acknowledgement is created at the chain time, not at the time of the Iroha block
+        let ack = IrohaBridge::pending_iroha_tx(0).unwrap();
+        assert_eq!(
+            ack.purpose,
+            crate::IrohaTxPurpose::IncomingTransfer(iroha_tx_hash, 0)
+        );
+        assert_eq!(ack.tx.payload.creation_time, 3_000);
+    });
+}
+
+#[test]
+fn should_sign_iroha_tx_with_all_local_authority_keys() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+        Timestamp::set_timestamp(3_000);
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice).into(),
+            iroha_tx_hash,
+            0,
+            sender,
+            receiver,
+            crate::ReceiverSource::Signatory,
+            XOR,
+            100
+        ));
+        let pending = IrohaBridge::pending_iroha_tx(0).unwrap();
+        let payload = pending.tx.payload.encode();
+        let signers = |tx: &RequestedTransaction| {
+            tx.signatures
+                .iter()
+                .map(|sig| {
+                    assert!(crate::utils::verify_iroha_sig(&payload, sig));
+                    sig.public_key.clone()
+                })
+                .collect::<BTreeSet<_>>()
+        };
+
+        // a node holding Alice's key only
+        let alice_tx = IrohaBridge::sign_iroha_tx(pending.tx.clone()).unwrap();
+        // a node catching up later, holding Bob's key and a key of no authority as well
+        sp_io::crypto::ed25519_generate(KEY_TYPE_2, Some(b"//Bob".to_vec()));
+        sp_io::crypto::ed25519_generate(KEY_TYPE_2, Some(b"//Eve".to_vec()));
+        Timestamp::set_timestamp(3_000 + crate::IROHA_TX_TIME_TO_LIVE_MS);
+        let all_tx = IrohaBridge::sign_iroha_tx(pending.tx.clone()).unwrap();
+
+        assert_eq!(alice_tx.payload.encode(), payload);
+        assert_eq!(all_tx.payload.encode(), payload);
+        assert_eq!(
+            signers(&alice_tx),
+            vec![authority_iroha_key("Alice")].into_iter().collect()
+        );
+        assert_eq!(
+            signers(&all_tx),
+            vec![authority_iroha_key("Alice"), authority_iroha_key("Bob")]
+                .into_iter()
+                .collect()
+        );
+    });
+}
+
+#[test]
+fn should_report_conflicting_attestations() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+
+        for (who, amount) in vec![(alice, 100), (bob.clone(), 1000)] {
+            assert_ok!(IrohaBridge::incoming_transfer(
+                Some(who).into(),
+                iroha_tx_hash,
+                0,
+                sender.clone(),
+                receiver.clone(),
+                crate::ReceiverSource::Signatory,
+                XOR,
+                amount,
+            ));
+        }
+
+        let disputed = Event::iroha_bridge(crate::RawEvent::IncomingTransferDisputed(
+            iroha_tx_hash,
+            0,
+            bob,
+        ));
+        assert!(System::events().iter().any(|r| r.event == disputed));
+        assert_eq!(
+            IrohaBridge::incoming_attestations((iroha_tx_hash, 0)).len(),
+            2
+        );
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver, XOR).unwrap(),
+            0
+        );
+    });
+}
+
+#[test]
+fn should_refund_incoming_transfer_below_threshold_after_timeout() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let xor = no_std_prelude::AssetDefinitionId::new("XOR", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+        let other_tx_hash = BlakeTwo256::hash(b"other iroha transaction");
+        // the authorities disagree on the receiver only
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice.clone()).into(),
+            iroha_tx_hash,
+            0,
+            sender.clone(),
+            charlie,
+            crate::ReceiverSource::Signatory,
+            XOR,
+            100,
+        ));
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(bob).into(),
+            iroha_tx_hash,
+            0,
+            sender.clone(),
+            dave.clone(),
+            crate::ReceiverSource::Signatory,
+            XOR,
+            100,
+        ));
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice.clone()).into(),
+            other_tx_hash,
+            0,
+            sender.clone(),
+            dave,
+            crate::ReceiverSource::Signatory,
+            XOR,
+            50,
+        ));
+
+        let timeout: u64 = <Test as crate::Trait>::IncomingAttestationTimeout::get();
+        IrohaBridge::on_initialize(timeout);
+        assert_eq!(
+            IrohaBridge::incoming_attestations((iroha_tx_hash, 0)).len(),
+            2
+        );
+        IrohaBridge::on_initialize(1 + timeout);
+        assert!(IrohaBridge::incoming_attestations((iroha_tx_hash, 0)).is_empty());
+        assert!(IrohaBridge::incoming_attestations((other_tx_hash, 0)).is_empty());
+        let expired =
+            Event::iroha_bridge(crate::RawEvent::IncomingTransferExpired(iroha_tx_hash, 0));
+        assert!(System::events().iter().any(|r| r.event == expired));
+
+        // both attestations count as votes to send the deposit back
+        assert!(IrohaBridge::processed_iroha_txs((iroha_tx_hash, 0)));
+        let refund = IrohaBridge::pending_iroha_tx(0).unwrap();
+        assert_eq!(
+            refund.purpose,
+            crate::IrohaTxPurpose::IncomingTransferRefund(iroha_tx_hash, 0)
+        );
+        let bridge_account = IrohaBridge::bridge_account_id();
+        assert_eq!(
+            refund.tx.payload.instructions,
+            vec![iroha_transfer(&bridge_account, &sender, xor.clone(), 100)]
+        );
+        let refunded =
+            Event::iroha_bridge(crate::RawEvent::IncomingTransferRefunded(iroha_tx_hash, 0));
+        assert!(System::events().iter().any(|r| r.event == refunded));
+
+        // a single attestation is a single vote
+        assert!(!IrohaBridge::processed_iroha_txs((other_tx_hash, 0)));
+        assert_eq!(
+            IrohaBridge::incoming_refund_votes((other_tx_hash, 0, sender, xor, 50)),
+            vec![alice]
+        );
+    });
+}
+
+#[test]
+fn should_manage_authority_set() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+        assert_eq!(IrohaBridge::authority_count(), 2);
+
+        assert_err!(
+            IrohaBridge::remove_authority(Some(alice.clone()).into(), bob.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(IrohaBridge::remove_authority(Origin::root(), bob.clone()));
+        assert!(!IrohaBridge::authorities().contains(&bob));
+        assert_eq!(
+            IrohaBridge::pending_signatory_updates(0),
+            Some((
+                crate::SignatoryUpdate::Remove(authority_iroha_key("Bob")),
+                0
+            ))
+        );
+        assert_err!(
+            IrohaBridge::set_threshold(Origin::root(), 2),
+            Error::<Test>::InvalidThreshold
+        );
+
+        assert_ok!(IrohaBridge::add_authority(
+            Origin::root(),
+            charlie.clone(),
+            authority_iroha_key("Charlie")
+        ));
+        assert_err!(
+            IrohaBridge::add_authority(
+                Origin::root(),
+                charlie.clone(),
+                authority_iroha_key("Dave")
+            ),
+            Error::<Test>::AlreadyAnAuthority
+        );
+        assert_err!(
+            IrohaBridge::add_authority(Origin::root(), dave.clone(), authority_iroha_key("Alice")),
+            Error::<Test>::DuplicateAuthorityKey
+        );
+        assert_ok!(IrohaBridge::set_threshold(Origin::root(), 2));
+        // the bridge account's quorum follows the threshold, which waits for it
+        assert_eq!(
+            IrohaBridge::pending_iroha_tx(2).unwrap().purpose,
+            crate::IrohaTxPurpose::QuorumUpdate(2)
+        );
+        assert_eq!(IrohaBridge::threshold(), 1);
+        assert_eq!(IrohaBridge::pending_threshold(), Some((2, 2)));
+        assert_err!(
+            IrohaBridge::remove_authority(Origin::root(), alice.clone()),
+            Error::<Test>::InvalidThreshold
+        );
+
+        // rotate to Charlie and Dave, keeping Charlie's key
+        assert_err!(
+            IrohaBridge::set_authorities(
+                Origin::root(),
+                vec![
+                    (dave.clone(), authority_iroha_key("Dave")),
+                    (dave.clone(), authority_iroha_key("Dave")),
+                ],
+                1
+            ),
+            Error::<Test>::DuplicateAuthority
+        );
+        assert_err!(
+            IrohaBridge::set_authorities(
+                Origin::root(),
+                vec![
+                    (charlie.clone(), authority_iroha_key("Dave")),
+                    (dave.clone(), authority_iroha_key("Dave")),
+                ],
+                1
+            ),
+            Error::<Test>::DuplicateAuthorityKey
+        );
+        assert_ok!(IrohaBridge::set_authorities(
+            Origin::root(),
+            vec![
+                (charlie.clone(), authority_iroha_key("Charlie")),
+                (dave.clone(), authority_iroha_key("Dave")),
+            ],
+            1
+        ));
+        let mut authorities = IrohaBridge::authorities();
+        authorities.sort();
+        let mut expected = vec![charlie.clone(), dave.clone()];
+        expected.sort();
+        assert_eq!(authorities, expected);
+        assert_eq!(IrohaBridge::threshold(), 1);
+        assert_eq!(IrohaBridge::pending_threshold(), Some((1, 4)));
+        // the new key is added before the quorum is lowered and the old key is removed
+        assert_eq!(
+            IrohaBridge::pending_signatory_updates(2),
+            Some((crate::SignatoryUpdate::Add(authority_iroha_key("Dave")), 3))
+        );
+        assert_eq!(
+            IrohaBridge::pending_iroha_tx(4).unwrap().purpose,
+            crate::IrohaTxPurpose::QuorumUpdate(1)
+        );
+        assert_eq!(
+            IrohaBridge::pending_signatory_updates(3),
+            Some((
+                crate::SignatoryUpdate::Remove(authority_iroha_key("Alice")),
+                5
+            ))
+        );
+        let removed = Event::iroha_bridge(crate::RawEvent::AuthorityRemoved(alice.clone()));
+        assert!(System::events().iter().any(|r| r.event == removed));
+
+        // an update is applied only once its transaction is committed on Iroha
+        assert_err!(
+            IrohaBridge::iroha_tx_committed(Some(alice).into(), 0),
+            Error::<Test>::NotAnAuthority
+        );
+        assert_ok!(IrohaBridge::iroha_tx_committed(Some(dave).into(), 0));
+        assert_eq!(IrohaBridge::pending_signatory_updates(0), None);
+        let applied = Event::iroha_bridge(crate::RawEvent::SignatoryUpdateApplied(0));
+        assert!(System::events().iter().any(|r| r.event == applied));
+
+        // Charlie's and Dave's keys are not signatories on Iroha yet
+        assert_err!(
+            IrohaBridge::set_threshold(Origin::root(), 2),
+            Error::<Test>::InvalidThreshold
+        );
+    });
+}
+
+#[test]
+fn should_keep_signing_as_the_bridge_account_through_a_full_key_swap() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let dave = get_account_id_from_seed::<sr25519::Public>("Dave");
+        let eve = get_account_id_from_seed::<sr25519::Public>("Eve");
+        sp_io::crypto::ed25519_generate(KEY_TYPE_2, Some(b"//Bob".to_vec()));
+        let signers = |iroha_tx_id| -> BTreeSet<_> {
+            let tx = IrohaBridge::pending_iroha_tx(iroha_tx_id).unwrap().tx;
+            IrohaBridge::sign_iroha_tx(tx)
+                .unwrap()
+                .signatures
+                .into_iter()
+                .map(|sig| sig.public_key)
+                .collect()
+        };
+        let keys = |seeds: &[&str]| -> BTreeSet<_> {
+            seeds.iter().map(|seed| authority_iroha_key(seed)).collect()
+        };
+        let commit = |who: &SubstrateAccountId, iroha_tx_id| {
+            assert_ok!(IrohaBridge::iroha_tx_committed(
+                Some(who.clone()).into(),
+                iroha_tx_id
+            ));
+        };
+
+        assert_ok!(IrohaBridge::set_threshold(Origin::root(), 2));
+        commit(&alice, 0);
+        assert_eq!(IrohaBridge::threshold(), 2);
+
+        // {Alice, Bob} needing 2 signatures are replaced by {Charlie, Dave} needing 1
+        assert_ok!(IrohaBridge::set_authorities(
+            Origin::root(),
+            vec![
+                (charlie.clone(), authority_iroha_key("Charlie")),
+                (dave.clone(), authority_iroha_key("Dave")),
+            ],
+            1
+        ));
+        assert_eq!(IrohaBridge::threshold(), 2);
+        assert_eq!(IrohaBridge::pending_threshold(), Some((1, 3)));
+        // Iroha still needs 2 signatures of Alice and Bob
+        assert_eq!(signers(1), keys(&["Alice", "Bob"]));
+        assert_eq!(signers(3), keys(&["Alice", "Bob"]));
+
+        // the threshold follows once the quorum is set and the new keys are added
+        commit(&charlie, 3);
+        commit(&dave, 3);
+        assert_eq!(IrohaBridge::threshold(), 2);
+        commit(&charlie, 1);
+        commit(&dave, 1);
+        commit(&charlie, 2);
+        commit(&dave, 2);
+        assert_eq!(IrohaBridge::threshold(), 1);
+        assert_eq!(IrohaBridge::pending_threshold(), None);
+        let changed = Event::iroha_bridge(crate::RawEvent::ThresholdChanged(1));
+        assert!(System::events().iter().any(|r| r.event == changed));
+
+        // the old keys sign until their removal is committed
+        sp_io::crypto::ed25519_generate(KEY_TYPE_2, Some(b"//Charlie".to_vec()));
+        assert_eq!(signers(4), keys(&["Alice", "Bob", "Charlie"]));
+        commit(&charlie, 4);
+        commit(&charlie, 5);
+        assert_ok!(IrohaBridge::add_authority(
+            Origin::root(),
+            eve,
+            authority_iroha_key("Eve")
+        ));
+        assert_eq!(signers(6), keys(&["Charlie"]));
+
+        // Eve's key is not a signatory on Iroha yet
+        assert_err!(
+            IrohaBridge::set_threshold(Origin::root(), 3),
+            Error::<Test>::InvalidThreshold
+        );
+        assert_ok!(IrohaBridge::set_threshold(Origin::root(), 2));
+    });
+}
+
+#[test]
+fn should_set_bridge_account() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let account = no_std_prelude::AccountId::new("bridge", "kusama");
+        assert_eq!(
+            IrohaBridge::bridge_account_id(),
+            no_std_prelude::AccountId::new("bridge", "polkadot")
+        );
+        assert_err!(
+            IrohaBridge::set_bridge_account(Some(alice.clone()).into(), account.clone()),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(IrohaBridge::set_bridge_account(
+            Origin::root(),
+            account.clone()
+        ));
+        assert_eq!(IrohaBridge::bridge_account_id(), account);
+        let changed = Event::iroha_bridge(crate::RawEvent::BridgeAccountChanged(account.clone()));
+        assert!(System::events().iter().any(|r| r.event == changed));
+
+        // transactions of the current account are only recognized as committed while it is used
+        let polkadot = no_std_prelude::AccountId::new("bridge", "polkadot");
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            no_std_prelude::AccountId::new("root", "global"),
+            DOT,
+            10,
+            0
+        ));
+        assert_err!(
+            IrohaBridge::set_bridge_account(Origin::root(), polkadot.clone()),
+            Error::<Test>::BridgeAccountInUse
+        );
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_eq!(
+            IrohaBridge::pending_iroha_tx(0)
+                .unwrap()
+                .tx
+                .payload
+                .account_id,
+            account
+        );
+        assert_err!(
+            IrohaBridge::set_bridge_account(Origin::root(), polkadot.clone()),
+            Error::<Test>::BridgeAccountInUse
+        );
+        assert_ok!(IrohaBridge::iroha_tx_committed(Some(alice).into(), 0));
+        assert_ok!(IrohaBridge::set_bridge_account(
+            Origin::root(),
+            polkadot.clone()
+        ));
+        assert_eq!(IrohaBridge::bridge_account_id(), polkadot);
+    });
+}
+
+#[test]
+fn should_use_bridge_account_and_definition_from_genesis() {
+    use sp_runtime::BuildStorage;
+    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+    let bridge_account = no_std_prelude::AccountId::new("bridge", "kusama");
+    let storage = GenesisConfig {
+        system: Some(frame_system::GenesisConfig::default()),
+        treasury: Some(TreasuryConfig {
+            assets: vec![(DOT, b"DOT".to_vec(), b"kusama".to_vec(), 0, 0)],
+            balances: vec![(DOT, alice.clone(), 100)],
+        }),
+        pallet_balances: None,
+        iroha_bridge: Some(IrohaBridgeConfig {
+            authorities: vec![],
+            iroha_peers: vec![],
+            iroha_endpoint: vec![],
+            threshold: 1,
+            bridge_account: Some(bridge_account.clone()),
+            bridge_definition: None,
+        }),
+    }
+    .build_storage()
+    .unwrap();
+    TestExternalities::from(storage).execute_with(|| {
+        System::set_block_number(1);
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let dot = no_std_prelude::AssetDefinitionId::new("DOT", "kusama");
+        assert_eq!(IrohaBridge::bridge_account_id(), bridge_account);
+        assert_eq!(
+            IrohaBridge::bridge_definition_id(),
+            BridgeDefinitionId::new("kusama")
+        );
+
+        // transfers to the bridge account of another deployment are not picked up
+        let instructions = vec![
+            iroha_transfer(
+                &sender,
+                &no_std_prelude::AccountId::new("bridge", "polkadot"),
+                dot.clone(),
+                10,
+            ),
+            iroha_transfer(&sender, &bridge_account, dot.clone(), 20),
+        ];
+        let transfers = IrohaBridge::bridge_transfers(instructions, &bridge_account);
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].isi_index, 1);
+        assert_eq!(transfers[0].asset.quantity, 20);
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            sender.clone(),
+            DOT,
+            100,
+            0
+        ));
+        let transfer = IrohaBridge::pending_outgoing(0).unwrap();
+        let expected = vec![bridge::isi::handle_incoming_transfer(
+            &BridgeDefinitionId::new("kusama"),
+            &dot,
+            100,
+            0,
+            sender,
+            &IrohaBridge::outgoing_external_tx(0, &transfer),
+        )];
+        assert_eq!(
+            IrohaBridge::outgoing_instructions(0, &transfer)
+                .unwrap()
+                .encode(),
+            expected.encode()
+        );
+
+        assert_ok!(IrohaBridge::set_bridge_definition(
+            Origin::root(),
+            BridgeDefinitionId::new("kusama-2")
+        ));
+        assert_eq!(
+            IrohaBridge::bridge_definition_id(),
+            BridgeDefinitionId::new("kusama-2")
+        );
+    });
+}
+
+#[test]
+fn should_find_bridge_transfers_in_composite_instructions() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let other = no_std_prelude::AccountId::new("alice", "wonderland");
+        let bridge_account = IrohaBridge::bridge_account_id();
+        let dot = no_std_prelude::AssetDefinitionId::new("DOT", "polkadot");
+        let asset_transfer = no_std_prelude::Instruction::Asset(AssetInstruction::TransferAsset(
+            sender.clone(),
+            bridge_account.clone(),
+            no_std_prelude::Asset::with_quantity(
+                no_std_prelude::AssetId::new(dot.clone(), sender.clone()),
+                4,
+            ),
+        ));
+        let instructions = vec![
+            no_std_prelude::Instruction::Pair(
+                Box::new(iroha_transfer(&sender, &bridge_account, dot.clone(), 1)),
+                Box::new(no_std_prelude::Instruction::Sequence(vec![
+                    iroha_transfer(&sender, &other, dot.clone(), 100),
+                    iroha_transfer(&sender, &bridge_account, dot.clone(), 2),
+                ])),
+            ),
+            iroha_transfer(&sender, &bridge_account, dot.clone(), 3),
+            asset_transfer,
+            no_std_prelude::Instruction::If(
+                Box::new(iroha_transfer(&sender, &other, dot.clone(), 100)),
+                Box::new(iroha_transfer(&sender, &bridge_account, dot.clone(), 5)),
+                Some(Box::new(iroha_transfer(
+                    &sender,
+                    &bridge_account,
+                    dot.clone(),
+                    6,
+                ))),
+            ),
+        ];
+
+        let transfers: Vec<_> = IrohaBridge::bridge_transfers(instructions, &bridge_account)
+            .into_iter()
+            .map(|t| (t.isi_index, t.asset.quantity, t.conditional))
+            .collect();
+        // top-level transfers keep their index, nested ones are numbered after the last one
+        assert_eq!(
+            transfers,
+            vec![
+                (1, 3, false),
+                (2, 4, false),
+                (4, 1, false),
+                (5, 2, false),
+                (6, 5, true),
+                (7, 6, true),
+            ]
+        );
+    });
+}
+
+#[test]
+fn should_report_conditional_incoming_transfer() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let other = no_std_prelude::AccountId::new("alice", "wonderland");
+        let bridge_account = IrohaBridge::bridge_account_id();
+        let xor = no_std_prelude::AssetDefinitionId::new("XOR", "global");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+
+        let instructions = vec![no_std_prelude::Instruction::If(
+            Box::new(iroha_transfer(&sender, &other, xor.clone(), 100)),
+            Box::new(iroha_transfer(&sender, &bridge_account, xor.clone(), 5)),
+            Some(Box::new(iroha_transfer(
+                &sender,
+                &bridge_account,
+                xor.clone(),
+                6,
+            ))),
+        )];
+        let tx = RequestedTransaction::new(
+            instructions,
+            sender.clone(),
+            crate::IROHA_TX_TIME_TO_LIVE_MS,
+            0,
+        );
+        let iroha_tx_hash = BlakeTwo256::hash(&tx.payload.encode());
+        let block = committed_block(1, [0u8; 32], 0, vec![tx], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        // neither branch is credited, both are reported
+        assert_eq!(
+            submitted_calls(&state),
+            vec![
+                crate::Call::conditional_incoming_transfer(
+                    iroha_tx_hash,
+                    1,
+                    sender.clone(),
+                    xor.clone(),
+                    5
+                ),
+                crate::Call::conditional_incoming_transfer(
+                    iroha_tx_hash,
+                    2,
+                    sender.clone(),
+                    xor.clone(),
+                    6
+                ),
+            ]
+        );
+
+        for (isi_index, quantity) in vec![(1, 5), (2, 6)] {
+            assert_ok!(IrohaBridge::conditional_incoming_transfer(
+                Some(alice.clone()).into(),
+                iroha_tx_hash,
+                isi_index,
+                sender.clone(),
+                xor.clone(),
+                quantity
+            ));
+        }
+        assert_err!(
+            IrohaBridge::conditional_incoming_transfer(
+                Some(alice.clone()).into(),
+                iroha_tx_hash,
+                1,
+                sender.clone(),
+                xor.clone(),
+                5
+            ),
+            Error::<Test>::IrohaTxAlreadyProcessed
+        );
+        assert_eq!(
+            IrohaBridge::conditional_transfer((iroha_tx_hash, 1)),
+            Some((sender, xor, 5))
+        );
+        let reported = Event::iroha_bridge(crate::RawEvent::ConditionalIncomingTransfer(
+            iroha_tx_hash,
+            1,
+        ));
+        assert!(System::events().iter().any(|r| r.event == reported));
+
+        assert_err!(
+            IrohaBridge::resolve_conditional_incoming_transfer(
+                Some(alice).into(),
+                iroha_tx_hash,
+                1,
+                Some(receiver.clone())
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(IrohaBridge::resolve_conditional_incoming_transfer(
+            Origin::root(),
+            iroha_tx_hash,
+            1,
+            Some(receiver.clone())
+        ));
+        assert_ok!(IrohaBridge::resolve_conditional_incoming_transfer(
+            Origin::root(),
+            iroha_tx_hash,
+            2,
+            None
+        ));
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver, XOR).unwrap(),
+            5
+        );
+        assert!(IrohaBridge::conditional_transfer((iroha_tx_hash, 1)).is_none());
+        let dropped = Event::iroha_bridge(crate::RawEvent::ConditionalIncomingTransferDropped(
+            iroha_tx_hash,
+            2,
+        ));
+        assert!(System::events().iter().any(|r| r.event == dropped));
+        assert_err!(
+            IrohaBridge::resolve_conditional_incoming_transfer(
+                Origin::root(),
+                iroha_tx_hash,
+                1,
+                None
+            ),
+            Error::<Test>::UnknownConditionalTransfer
+        );
+    });
+}
+
+#[test]
+fn should_refund_transfers_of_unregistered_or_disabled_assets() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let bridge_account = IrohaBridge::bridge_account_id();
+        let eur = no_std_prelude::AssetDefinitionId::new("EUR", "polkadot");
+        let dot = no_std_prelude::AssetDefinitionId::new("DOT", "polkadot");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+        assert_ok!(Treasury::set_asset_enabled(Origin::root(), DOT, false));
+
+        let tx = RequestedTransaction::new(
+            vec![
+                iroha_transfer(&sender, &bridge_account, eur.clone(), 3),
+                no_std_prelude::Instruction::Sequence(vec![iroha_transfer(
+                    &sender,
+                    &bridge_account,
+                    dot.clone(),
+                    4,
+                )]),
+            ],
+            sender.clone(),
+            crate::IROHA_TX_TIME_TO_LIVE_MS,
+            0,
+        );
+        let tx_hash = BlakeTwo256::hash(&tx.payload.encode());
+        let block = committed_block(1, [0u8; 32], 0, vec![tx], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(
+            submitted_calls(&state),
+            vec![
+                crate::Call::refund_incoming_transfer(tx_hash, 0, sender.clone(), eur, 3),
+                crate::Call::refund_incoming_transfer(tx_hash, 2, sender, dot, 4),
+            ]
+        );
+    });
+}
+
+#[test]
+fn should_decode_ss58_memo() {
+    let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+    let address = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+    assert_eq!(crate::utils::decode_ss58::<Test>(address), Some(alice));
+    // wrong checksum
+    assert_eq!(
+        crate::utils::decode_ss58::<Test>("5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ"),
+        None
+    );
+    // Alice's address on a chain with another address type
+    assert_eq!(
+        crate::utils::decode_ss58::<Test>("15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"),
+        None
+    );
+    assert_eq!(crate::utils::decode_ss58::<Test>("not an address"), None);
+    assert_eq!(crate::utils::decode_ss58::<Test>(""), None);
+
+    let sender = no_std_prelude::AccountId::new("root", "global");
+    let bridge_account = no_std_prelude::AccountId::new("bridge", "polkadot");
+    let dot = no_std_prelude::AssetDefinitionId::new("DOT", "polkadot");
+    let transfer = iroha_transfer(&sender, &bridge_account, dot, 1);
+    assert_eq!(IrohaBridge::transfer_memo(&[transfer.clone()]), None);
+    assert_eq!(
+        IrohaBridge::transfer_memo(&[
+            transfer,
+            no_std_prelude::Instruction::Notify(address.to_string()),
+            no_std_prelude::Instruction::Notify("ignored".to_string()),
+        ]),
+        Some(address.to_string())
+    );
+}
+
+#[test]
+fn should_credit_memo_recipient_and_refund_invalid_memo() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let bridge_account = IrohaBridge::bridge_account_id();
+        let xor = no_std_prelude::AssetDefinitionId::new("XOR", "global");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+
+        let memo_tx = RequestedTransaction::new(
+            vec![
+                iroha_transfer(&sender, &bridge_account, xor.clone(), 5),
+                no_std_prelude::Instruction::Notify(charlie.to_ss58check()),
+            ],
+            sender.clone(),
+            crate::IROHA_TX_TIME_TO_LIVE_MS,
+            0,
+        );
+        // Alice's address on a chain with another address type
+        let invalid_memo_tx = RequestedTransaction::new(
+            vec![
+                iroha_transfer(&sender, &bridge_account, xor.clone(), 7),
+                no_std_prelude::Instruction::Notify(
+                    "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5".to_string(),
+                ),
+            ],
+            sender.clone(),
+            crate::IROHA_TX_TIME_TO_LIVE_MS,
+            1,
+        );
+        let memo_tx_hash = BlakeTwo256::hash(&memo_tx.payload.encode());
+        let invalid_memo_tx_hash = BlakeTwo256::hash(&invalid_memo_tx.payload.encode());
+        let block = committed_block(
+            1,
+            [0u8; 32],
+            0,
+            vec![memo_tx, invalid_memo_tx],
+            &[&pairs[0]],
+        );
+        share_blocks(&oc_state, vec![block]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(
+            submitted_calls(&state),
+            vec![
+                crate::Call::incoming_transfer(
+                    memo_tx_hash,
+                    0,
+                    sender.clone(),
+                    charlie.clone(),
+                    crate::ReceiverSource::Memo,
+                    XOR,
+                    5
+                ),
+                crate::Call::refund_incoming_transfer(
+                    invalid_memo_tx_hash,
+                    0,
+                    sender.clone(),
+                    xor.clone(),
+                    7
+                ),
+            ]
+        );
+
+        // the account named in a memo is not used for later transfers without one
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice.clone()).into(),
+            memo_tx_hash,
+            0,
+            sender.clone(),
+            charlie.clone(),
+            crate::ReceiverSource::Memo,
+            XOR,
+            5
+        ));
+        assert_eq!(
+            Treasury::get_balance_from_account(charlie.clone(), XOR).unwrap(),
+            5
+        );
+        assert!(!crate::Accounts::<Test>::contains_key(&sender));
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice.clone()).into(),
+            memo_tx_hash,
+            1,
+            sender.clone(),
+            charlie.clone(),
+            crate::ReceiverSource::Signatory,
+            XOR,
+            1
+        ));
+        assert_eq!(crate::Accounts::<Test>::get(&sender), charlie);
+
+        assert_ok!(IrohaBridge::refund_incoming_transfer(
+            Some(alice).into(),
+            invalid_memo_tx_hash,
+            0,
+            sender,
+            xor,
+            7
+        ));
+        assert!(IrohaBridge::processed_iroha_txs((invalid_memo_tx_hash, 0)));
+        assert_eq!(
+            IrohaBridge::pending_iroha_tx(2).unwrap().purpose,
+            crate::IrohaTxPurpose::IncomingTransferRefund(invalid_memo_tx_hash, 0)
+        );
+        let refunded = Event::iroha_bridge(crate::RawEvent::IncomingTransferRefunded(
+            invalid_memo_tx_hash,
+            0,
+        ));
+        assert!(System::events().iter().any(|r| r.event == refunded));
+    });
+}
+
+#[test]
+fn should_link_and_unlink_iroha_account() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let charlie = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let iroha_account = no_std_prelude::AccountId::new("alice", "wonderland");
+        let iroha_key = ed25519::Pair::from_string("//AliceIroha", None).unwrap();
+        let squatter_key = ed25519::Pair::from_string("//Mallory", None).unwrap();
+        let link_signature = |pair: &ed25519::Pair, who: &SubstrateAccountId| {
+            iroha_signature(pair, &IrohaBridge::link_message(&iroha_account, who))
+        };
+
+        // signed over another account id
+        assert_err!(
+            IrohaBridge::link_iroha_account(
+                Some(alice.clone()).into(),
+                iroha_account.clone(),
+                link_signature(&iroha_key, &bob)
+            ),
+            Error::<Test>::InvalidLinkSignature
+        );
+        // a signature of the bare account id may have been made for another purpose
+        assert_err!(
+            IrohaBridge::link_iroha_account(
+                Some(alice.clone()).into(),
+                iroha_account.clone(),
+                iroha_signature(&iroha_key, &alice.encode())
+            ),
+            Error::<Test>::InvalidLinkSignature
+        );
+        assert_ok!(IrohaBridge::link_iroha_account(
+            Some(alice.clone()).into(),
+            iroha_account.clone(),
+            link_signature(&iroha_key, &alice)
+        ));
+        // any key can sign, so a pending link doesn't block the owner
+        assert_ok!(IrohaBridge::link_iroha_account(
+            Some(bob.clone()).into(),
+            iroha_account.clone(),
+            link_signature(&squatter_key, &bob)
+        ));
+        assert_eq!(IrohaBridge::linked_account(&iroha_account), None);
+        assert_eq!(
+            IrohaBridge::pending_link((iroha_account.clone(), alice.clone())),
+            Some(iroha_public_key(&iroha_key))
+        );
+
+        // the offchain workers don't reject a link when Iroha can't be queried
+        oc_state.write().http_failure_code = Some(500);
+        assert_ok!(IrohaBridge::check_iroha_account_links());
+        assert!(submitted_calls(&state).is_empty());
+
+        assert_err!(
+            IrohaBridge::check_iroha_account_link(
+                Some(charlie).into(),
+                iroha_account.clone(),
+                alice.clone(),
+                iroha_public_key(&iroha_key),
+                true
+            ),
+            Error::<Test>::NotAnAuthority
+        );
+        assert_err!(
+            IrohaBridge::check_iroha_account_link(
+                Some(alice.clone()).into(),
+                iroha_account.clone(),
+                alice.clone(),
+                iroha_public_key(&squatter_key),
+                true
+            ),
+            Error::<Test>::UnknownLinkRequest
+        );
+        assert_ok!(IrohaBridge::check_iroha_account_link(
+            Some(alice.clone()).into(),
+            iroha_account.clone(),
+            bob.clone(),
+            iroha_public_key(&squatter_key),
+            false
+        ));
+        assert_eq!(
+            IrohaBridge::pending_link((iroha_account.clone(), bob.clone())),
+            None
+        );
+        let rejected = Event::iroha_bridge(crate::RawEvent::IrohaAccountLinkRejected(
+            bob.clone(),
+            iroha_account.clone(),
+        ));
+        assert!(System::events().iter().any(|r| r.event == rejected));
+
+        assert_ok!(IrohaBridge::check_iroha_account_link(
+            Some(alice.clone()).into(),
+            iroha_account.clone(),
+            alice.clone(),
+            iroha_public_key(&iroha_key),
+            true
+        ));
+        assert_eq!(
+            IrohaBridge::linked_account(&iroha_account),
+            Some((alice.clone(), iroha_public_key(&iroha_key)))
+        );
+        assert_err!(
+            IrohaBridge::link_iroha_account(
+                Some(bob.clone()).into(),
+                iroha_account.clone(),
+                link_signature(&iroha_key, &bob)
+            ),
+            Error::<Test>::IrohaAccountAlreadyLinked
+        );
+        let linked = Event::iroha_bridge(crate::RawEvent::IrohaAccountLinked(
+            alice.clone(),
+            iroha_account.clone(),
+        ));
+        assert!(System::events().iter().any(|r| r.event == linked));
+
+        assert_err!(
+            IrohaBridge::unlink_iroha_account(Some(bob.clone()).into(), iroha_account.clone()),
+            Error::<Test>::IrohaAccountNotLinked
+        );
+        assert_ok!(IrohaBridge::unlink_iroha_account(
+            Some(alice.clone()).into(),
+            iroha_account.clone()
+        ));
+        assert_eq!(IrohaBridge::linked_account(&iroha_account), None);
+        let unlinked = Event::iroha_bridge(crate::RawEvent::IrohaAccountUnlinked(
+            alice,
+            iroha_account.clone(),
+        ));
+        assert!(System::events().iter().any(|r| r.event == unlinked));
+
+        // once unlinked, another account can prove the ownership
+        assert_ok!(IrohaBridge::link_iroha_account(
+            Some(bob.clone()).into(),
+            iroha_account.clone(),
+            link_signature(&iroha_key, &bob)
+        ));
+        assert_eq!(
+            IrohaBridge::pending_link((iroha_account, bob)),
+            Some(iroha_public_key(&iroha_key))
+        );
+    });
+}
+
+#[test]
+fn should_burn_outgoing_transfer_after_approvals() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let initial = Treasury::get_balance_from_account(alice.clone(), DOT).unwrap();
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        assert_eq!(
+            Treasury::get_balance_from_account(alice.clone(), DOT).unwrap(),
+            initial - 100
+        );
+        assert_err!(
+            IrohaBridge::iroha_tx_committed(Some(alice.clone()).into(), 0),
+            Error::<Test>::UnknownIrohaTx
+        );
+
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_err!(
+            IrohaBridge::approve_outgoing_transfer(Some(alice.clone()).into(), 0),
+            Error::<Test>::AlreadyApproved
+        );
+        assert_eq!(
+            IrohaBridge::pending_outgoing(0).unwrap().status,
+            crate::OutgoingTransferStatus::Requested
+        );
+        Timestamp::set_timestamp(2_000);
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(bob.clone()).into(),
+            0
+        ));
+        let transfer = IrohaBridge::pending_outgoing(0).unwrap();
+        assert_eq!(transfer.status, crate::OutgoingTransferStatus::Approved);
+        assert_eq!(transfer.iroha_tx, Some(0));
+        // the Iroha transaction all authorities sign is created at the approval time
+        let pending = IrohaBridge::pending_iroha_tx(0).unwrap();
+        assert_eq!(pending.purpose, crate::IrohaTxPurpose::OutgoingTransfer(0));
+        assert_eq!(pending.tx.payload.creation_time, 2_000);
+        assert_eq!(
+            pending.tx.payload.instructions,
+            IrohaBridge::outgoing_instructions(0, &transfer).unwrap()
+        );
+
+        // the funds are burned only once the transaction is committed on Iroha
+        assert_ok!(IrohaBridge::iroha_tx_committed(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_err!(
+            IrohaBridge::iroha_tx_committed(Some(alice.clone()).into(), 0),
+            Error::<Test>::AlreadyReported
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            100
+        );
+        assert_ok!(IrohaBridge::iroha_tx_committed(Some(bob).into(), 0));
+        assert!(IrohaBridge::pending_outgoing(0).is_none());
+        assert!(IrohaBridge::pending_iroha_tx(0).is_none());
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            0
+        );
+        assert_eq!(
+            Treasury::get_balance_from_account(alice.clone(), DOT).unwrap(),
+            initial - 100
+        );
+        let locked = Event::treasury(treasury::RawEvent::Lock(alice.clone(), DOT, 100, Some(0)));
+        let burned = Event::treasury(treasury::RawEvent::Burn(alice, DOT, 100, Some(0)));
+        assert!(System::events().iter().any(|r| r.event == locked));
+        assert!(System::events().iter().any(|r| r.event == burned));
+    });
+}
+
+#[test]
+fn should_report_outgoing_transfer_once_committed_on_iroha() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+
+        // an Iroha peer accepting the transaction does not finalize the transfer
+        oc_state.write().http_responses.push_back(vec![]);
+        IrohaBridge::send_iroha_txs();
+        IrohaBridge::send_iroha_txs();
+        assert_eq!(submitted_calls(&state), vec![crate::Call::iroha_tx_sent(0)]);
+        state.write().transactions.clear();
+
+        let tx = IrohaBridge::pending_iroha_tx(0).unwrap().tx;
+        let block = committed_block(1, [0u8; 32], 0, vec![tx], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block.clone()]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        share_blocks(&oc_state, vec![block]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(
+            submitted_calls(&state),
+            vec![crate::Call::iroha_tx_committed(0)]
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice, DOT).unwrap(),
+            100
+        );
+    });
+}
+
+#[test]
+fn should_lock_and_release_native_asset() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+        let escrow = Treasury::escrow_account();
+        let initial = Balances::free_balance(&alice);
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            sender.clone(),
+            NATIVE,
+            100,
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_ok!(IrohaBridge::iroha_tx_committed(
+            Some(alice.clone()).into(),
+            0
+        ));
+        // the funds stay in escrow instead of being burned
+        assert_eq!(Balances::free_balance(&alice), initial - 100);
+        assert_eq!(Balances::free_balance(&escrow), 100);
+        assert_eq!(Treasury::total_escrowed(NATIVE), 100);
+
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice.clone()).into(),
+            iroha_tx_hash,
+            0,
+            sender.clone(),
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            NATIVE,
+            60
+        ));
+        assert_eq!(Balances::free_balance(&receiver), 60);
+        assert_eq!(Balances::free_balance(&escrow), 40);
+
+        // more than the escrow holds
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(alice).into(),
+            iroha_tx_hash,
+            1,
+            sender,
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            NATIVE,
+            60
+        ));
+        let rejected =
+            Event::iroha_bridge(crate::RawEvent::IncomingTransferRejected(iroha_tx_hash, 1));
+        assert!(System::events().iter().any(|r| r.event == rejected));
+        assert_eq!(Balances::free_balance(&receiver), 60);
+        // only the credited transfer is acknowledged to Iroha
+        assert!(IrohaBridge::pending_iroha_tx(1).is_some());
+        assert!(IrohaBridge::pending_iroha_tx(2).is_none());
+    });
+}
+
+#[test]
+fn should_refund_incoming_transfer_the_escrow_cannot_release() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let bridge_account = IrohaBridge::bridge_account_id();
+        let unit = no_std_prelude::AssetDefinitionId::new("UNIT", "polkadot");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+        assert_eq!(Treasury::total_escrowed(NATIVE), 0);
+
+        let tx = RequestedTransaction::new(
+            vec![iroha_transfer(&sender, &bridge_account, unit.clone(), 5)],
+            sender.clone(),
+            crate::IROHA_TX_TIME_TO_LIVE_MS,
+            0,
+        );
+        let tx_hash = BlakeTwo256::hash(&tx.payload.encode());
+        let block = committed_block(1, [0u8; 32], 0, vec![tx], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(
+            submitted_calls(&state),
+            vec![crate::Call::refund_incoming_transfer(
+                tx_hash, 0, sender, unit, 5
+            )]
+        );
+    });
+}
+
+#[test]
+fn should_unlock_outgoing_transfer_after_retries() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let initial = Treasury::get_balance_from_account(alice.clone(), DOT).unwrap();
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        let mut deadline = IrohaBridge::pending_outgoing(0).unwrap().deadline;
+        IrohaBridge::on_initialize(deadline - 1);
+        assert!(IrohaBridge::pending_outgoing(0).is_some());
+
+        for retry in 1..=MaxOutgoingTransferRetries::get() {
+            IrohaBridge::on_initialize(deadline);
+            let transfer = IrohaBridge::pending_outgoing(0).unwrap();
+            assert_eq!(transfer.retries, retry);
+            assert_eq!(transfer.deadline, deadline + OutgoingTransferTimeout::get());
+            let retried = Event::iroha_bridge(crate::RawEvent::OutgoingTransferRetried(0, retry));
+            assert!(System::events().iter().any(|r| r.event == retried));
+            deadline = transfer.deadline;
+        }
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            100
+        );
+
+        IrohaBridge::on_initialize(deadline);
+        assert!(IrohaBridge::pending_outgoing(0).is_none());
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            0
+        );
+        assert_eq!(
+            Treasury::get_balance_from_account(alice, DOT).unwrap(),
+            initial
+        );
+        let expired = Event::iroha_bridge(crate::RawEvent::OutgoingTransferExpired(0));
+        assert!(System::events().iter().any(|r| r.event == expired));
+    });
+}
+
+#[test]
+fn should_not_expire_approved_outgoing_transfer() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+
+        let mut deadline = IrohaBridge::pending_outgoing(0).unwrap().deadline;
+        for _ in 0..=MaxOutgoingTransferRetries::get() {
+            IrohaBridge::on_initialize(deadline);
+            deadline += OutgoingTransferTimeout::get();
+        }
+        let transfer = IrohaBridge::pending_outgoing(0).unwrap();
+        assert_eq!(transfer.status, crate::OutgoingTransferStatus::Approved);
+        assert_eq!(transfer.retries, 0);
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice, DOT).unwrap(),
+            100
+        );
+    });
+}
+
+#[test]
+fn should_not_refund_outgoing_transfer_when_iroha_is_unavailable() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        oc_state.write().http_failure_code = Some(500);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        seal_block(1, state.clone(), oc_state.clone());
+        seal_block(2, state.clone(), oc_state.clone());
+        seal_block(3, state, oc_state);
+        // a failed request doesn't tell whether Iroha got the transaction
+        assert_eq!(
+            IrohaBridge::pending_outgoing(0).unwrap().status,
+            crate::OutgoingTransferStatus::Approved
+        );
+        assert!(!IrohaBridge::pending_iroha_tx(0).unwrap().sent);
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice, DOT).unwrap(),
+            100
+        );
+    });
+}
+
+#[test]
+fn should_refund_outgoing_transfer_after_threshold_votes() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        assert_err!(
+            IrohaBridge::refund_outgoing_transfer(Some(alice.clone()).into(), 0, vec![]),
+            Error::<Test>::InvalidRequestStatus
+        );
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(bob.clone()).into(),
+            0
+        ));
+        assert_err!(
+            IrohaBridge::refund_outgoing_transfer(Some(alice.clone()).into(), 0, vec![]),
+            Error::<Test>::IrohaTxNotExpired
+        );
+
+        Timestamp::set_timestamp(crate::IROHA_TX_TIME_TO_LIVE_MS + 1);
+        assert_ok!(IrohaBridge::refund_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0,
+            b"rejected".to_vec()
+        ));
+        assert_err!(
+            IrohaBridge::refund_outgoing_transfer(Some(alice.clone()).into(), 0, vec![]),
+            Error::<Test>::AlreadyVotedForRefund
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            100
+        );
+
+        assert_ok!(IrohaBridge::refund_outgoing_transfer(
+            Some(bob).into(),
+            0,
+            b"rejected".to_vec()
+        ));
+        assert!(IrohaBridge::pending_outgoing(0).is_none());
+        assert!(IrohaBridge::pending_iroha_tx(0).is_none());
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            0
+        );
+        assert_err!(
+            IrohaBridge::refund_outgoing_transfer(Some(alice).into(), 0, vec![]),
+            Error::<Test>::UnknownRequest
+        );
+    });
+}
+
+#[test]
+fn should_requeue_sent_outgoing_transfer_instead_of_refunding() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        crate::Threshold::put(2);
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver,
+            DOT,
+            100,
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(alice.clone()).into(),
+            0
+        ));
+        assert_ok!(IrohaBridge::approve_outgoing_transfer(
+            Some(bob.clone()).into(),
+            0
+        ));
+        assert_ok!(IrohaBridge::iroha_tx_sent(Some(bob.clone()).into(), 0));
+        assert_err!(
+            IrohaBridge::iroha_tx_expired(Some(alice.clone()).into(), 0),
+            Error::<Test>::IrohaTxNotExpired
+        );
+
+        let expiry = crate::IROHA_TX_TIME_TO_LIVE_MS + 1;
+        Timestamp::set_timestamp(expiry);
+        assert_err!(
+            IrohaBridge::iroha_tx_sent(Some(alice.clone()).into(), 0),
+            Error::<Test>::IrohaTxExpired
+        );
+        assert_err!(
+            IrohaBridge::refund_outgoing_transfer(Some(alice.clone()).into(), 0, vec![]),
+            Error::<Test>::OutgoingTransferAlreadySent
+        );
+        let old_hash = IrohaBridge::pending_iroha_tx(0).unwrap().hash;
+        assert_ok!(IrohaBridge::iroha_tx_expired(Some(alice.clone()).into(), 0));
+        assert_err!(
+            IrohaBridge::iroha_tx_expired(Some(alice.clone()).into(), 0),
+            Error::<Test>::AlreadyReported
+        );
+        assert_ok!(IrohaBridge::iroha_tx_expired(Some(bob).into(), 0));
+
+        let pending = IrohaBridge::pending_iroha_tx(0).unwrap();
+        assert!(!pending.sent);
+        assert_eq!(pending.tx.payload.creation_time, expiry);
+        assert_ne!(pending.hash, old_hash);
+        assert_eq!(
+            IrohaBridge::pending_outgoing(0).unwrap().status,
+            crate::OutgoingTransferStatus::Approved
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice, DOT).unwrap(),
+            100
+        );
+        let requeued = Event::iroha_bridge(crate::RawEvent::IrohaTxRequeued(0));
+        assert!(System::events().iter().any(|r| r.event == requeued));
+    });
+}
+
+#[test]
+fn should_report_iroha_tx_expiry_seen_in_iroha_blocks() {
+    let (mut ext, state, oc_state) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let pairs = peer_pairs(1);
+        assert_ok!(IrohaBridge::set_iroha_peers(
+            Origin::root(),
+            vec![iroha_public_key(&pairs[0])],
+            0
+        ));
+        for nonce in 0..2 {
+            assert_ok!(IrohaBridge::request_transfer(
+                Some(alice.clone()).into(),
+                receiver.clone(),
+                DOT,
+                100,
+                nonce
+            ));
+            assert_ok!(IrohaBridge::approve_outgoing_transfer(
+                Some(alice.clone()).into(),
+                nonce
+            ));
+        }
+        assert_ok!(IrohaBridge::iroha_tx_sent(Some(alice).into(), 1));
+
+        let ttl = crate::IROHA_TX_TIME_TO_LIVE_MS;
+        Timestamp::set_timestamp(ttl + 1);
+        // Iroha could still commit the transactions in a block created before their expiry
+        let block_1 = committed_block(1, [0u8; 32], ttl, vec![], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block_1.clone()]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert!(submitted_calls(&state).is_empty());
+
+        let block_2 = committed_block(2, iroha_block_hash(&block_1), ttl + 1, vec![], &[&pairs[0]]);
+        share_blocks(&oc_state, vec![block_2]);
+        assert_ok!(IrohaBridge::fetch_iroha());
+        assert_eq!(
+            submitted_calls(&state),
+            vec![
+                crate::Call::refund_outgoing_transfer(0, b"IrohaTxExpired".to_vec()),
+                crate::Call::iroha_tx_expired(1),
+            ]
+        );
+    });
+}
+
+#[test]
+fn should_reject_duplicate_outgoing_transfer_nonce() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let bob = get_account_id_from_seed::<sr25519::Public>("Bob");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let nonce = u64::from(u8::MAX) + 1;
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver.clone(),
+            DOT,
+            10,
+            nonce
+        ));
+        assert_err!(
+            IrohaBridge::request_transfer(
+                Some(alice.clone()).into(),
+                receiver.clone(),
+                DOT,
+                10,
+                nonce
+            ),
+            Error::<Test>::DuplicateNonce
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), DOT).unwrap(),
+            10
+        );
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(bob).into(),
+            receiver,
+            DOT,
+            10,
+            nonce
+        ));
+        assert!(IrohaBridge::used_nonces(&alice, nonce));
+    });
+}
+
+#[test]
+fn should_link_outgoing_transfer_to_requesting_extrinsic() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+
+        System::set_extrinsic_index(3);
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver.clone(),
+            DOT,
+            100,
+            7
+        ));
+        frame_system::BlockHash::<Test>::insert(1, Hash::repeat_byte(0xab));
+
+        let transfer = IrohaBridge::pending_outgoing(0).unwrap();
+        let tx = IrohaBridge::outgoing_external_tx(0, &transfer);
+        assert_eq!(tx.hash, "ab".repeat(32));
+        let decoded = <(
+            u32,
+            u64,
+            SubstrateAccountId,
+            no_std_prelude::AccountId,
+            treasury::AssetId,
+            u128,
+            u64,
+        )>::decode(&mut tx.payload.as_slice())
+        .unwrap();
+        assert_eq!(decoded, (3, 0, alice, receiver, DOT, 100, 7));
+    });
+}
+
+#[test]
+fn should_scale_transfers_by_asset_decimals() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let authority = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = get_account_id_from_seed::<sr25519::Public>("Charlie");
+        let sender = no_std_prelude::AccountId::new("root", "global");
+        let iroha_tx_hash = BlakeTwo256::hash(b"iroha transaction");
+        let usd = 3;
+        // 2 decimals on this chain, 4 on Iroha
+        assert_ok!(Treasury::register_asset(
+            Origin::root(),
+            usd,
+            no_std_prelude::AssetDefinitionId::new("USD", "polkadot"),
+            b"USD".to_vec(),
+            2,
+            4,
+            treasury::AssetMode::MintBurn
+        ));
+
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(authority.clone()).into(),
+            iroha_tx_hash,
+            0,
+            sender.clone(),
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            usd,
+            12_300
+        ));
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver.clone(), usd).unwrap(),
+            123
+        );
+
+        assert_ok!(IrohaBridge::incoming_transfer(
+            Some(authority).into(),
+            iroha_tx_hash,
+            1,
+            sender,
+            receiver.clone(),
+            crate::ReceiverSource::Signatory,
+            usd,
+            12_345
+        ));
+        let rejected =
+            Event::iroha_bridge(crate::RawEvent::IncomingTransferRejected(iroha_tx_hash, 1));
+        assert!(System::events().iter().any(|r| r.event == rejected));
+        assert!(IrohaBridge::processed_iroha_txs((iroha_tx_hash, 1)));
+        assert_eq!(
+            Treasury::get_balance_from_account(receiver.clone(), usd).unwrap(),
+            123
+        );
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(receiver.clone()).into(),
+            no_std_prelude::AccountId::new("root", "global"),
+            usd,
+            23,
+            0
+        ));
+        assert_eq!(
+            Treasury::to_iroha_quantity(usd, IrohaBridge::pending_outgoing(0).unwrap().amount)
+                .unwrap(),
+            2_300
+        );
+    });
+}
+
+#[test]
+fn should_reject_outgoing_transfer_losing_precision() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let eur = 3;
+        // 4 decimals on this chain, 2 on Iroha
+        assert_ok!(Treasury::register_asset(
+            Origin::root(),
+            eur,
+            no_std_prelude::AssetDefinitionId::new("EUR", "polkadot"),
+            b"EUR".to_vec(),
+            4,
+            2,
+            treasury::AssetMode::MintBurn
+        ));
+        assert_ok!(Treasury::mint(alice.clone(), eur, 10_000));
+
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            no_std_prelude::AccountId::new("root", "global"),
+            eur,
+            101,
+            0
+        ));
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(alice.clone(), eur).unwrap(),
+            0
+        );
+        assert!(IrohaBridge::pending_outgoing(0).is_none());
+        let rejected = Event::iroha_bridge(crate::RawEvent::OutgoingTransferRejected(
+            alice.clone(),
+            eur,
+            101,
+        ));
+        assert!(System::events().iter().any(|r| r.event == rejected));
+        // the nonce of a rejected request is not used up
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice).into(),
+            no_std_prelude::AccountId::new("root", "global"),
+            eur,
+            100,
+            0
+        ));
+    });
+}
+
+#[test]
+fn should_split_quantity_into_iroha_instructions() {
+    let max = u128::from(u32::MAX);
+    assert_eq!(crate::utils::split_quantity(0), vec![0]);
+    assert_eq!(crate::utils::split_quantity(max), vec![u32::MAX]);
+    assert_eq!(crate::utils::split_quantity(max + 1), vec![u32::MAX, 1]);
+    assert_eq!(
+        crate::utils::split_quantity(max * 3),
+        vec![u32::MAX, u32::MAX, u32::MAX]
+    );
+}
+
+#[test]
+fn should_apply_oversized_transfer_policy() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        let alice = get_account_id_from_seed::<sr25519::Public>("Alice");
+        let receiver = no_std_prelude::AccountId::new("root", "global");
+        let max = u128::from(u32::MAX);
+        assert_ok!(Treasury::mint(alice.clone(), XOR, max * 20));
+
+        OversizedTransfers::set(crate::OversizedTransferPolicy::Reject);
+        assert_err!(
+            IrohaBridge::request_transfer(
+                Some(alice.clone()).into(),
+                receiver.clone(),
+                XOR,
+                max + 1,
+                0
+            ),
+            Error::<Test>::AmountTooLarge
+        );
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver.clone(),
+            XOR,
+            max,
+            1
+        ));
+
+        OversizedTransfers::set(crate::OversizedTransferPolicy::Split);
+        assert_ok!(IrohaBridge::request_transfer(
+            Some(alice.clone()).into(),
+            receiver.clone(),
+            XOR,
+            max + 1,
+            2
+        ));
+        assert_err!(
+            IrohaBridge::request_transfer(
+                Some(alice).into(),
+                receiver,
+                XOR,
+                max * crate::MAX_OUTGOING_TRANSFER_PARTS + 1,
+                3
+            ),
+            Error::<Test>::AmountTooLarge
+        );
+    });
+}
+
+#[test]
+fn should_reject_malformed_iroha_endpoint() {
+    let (mut ext, _, _) = ExtBuilder::build();
+    ext.execute_with(|| {
+        for endpoint in &[
+            &b""[..],
+            b"127.0.0.1:7878",
+            b"ftp://127.0.0.1:7878",
+            b"http://",
+            b"http://:7878",
+            b"http://127.0.0.1:port",
+            b"http://127.0.0.1:7878?x=1",
+            b"http://user@127.0.0.1",
+            b"http://127.0.0.1 :7878",
+            b"http://\xff",
+        ] {
+            assert_err!(
+                IrohaBridge::set_iroha_endpoint(Origin::root(), endpoint.to_vec()),
+                Error::<Test>::InvalidIrohaEndpoint
+            );
+        }
+        assert_eq!(
+            IrohaBridge::iroha_endpoint(),
+            b"http://127.0.0.1:7878".to_vec()
+        );
+
+        for endpoint in &[
+            &b"https://iroha.example.com"[..],
+            b"http://10.0.0.1:8080/api/",
+        ] {
+            assert_ok!(IrohaBridge::set_iroha_endpoint(
+                Origin::root(),
+                endpoint.to_vec()
+            ));
+            assert_eq!(IrohaBridge::iroha_endpoint(), endpoint.to_vec());
+        }
+        assert_eq!(
+            IrohaBridge::iroha_url().ok(),
+            Some("http://10.0.0.1:8080/api".to_string())
+        );
+    });
+}
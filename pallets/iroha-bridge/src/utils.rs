@@ -1,8 +1,13 @@
 use crate::Trait;
+use alloc::string::String;
+use alloc::vec::Vec;
 use core::convert::TryFrom;
+use frame_support::traits::Get;
 use frame_system::offchain::SigningTypes;
 use iroha_client_no_std::crypto as iroha_crypto;
 use parity_scale_codec::{Decode, Encode};
+use sp_core::ed25519;
+use sp_core::hexdisplay::HexDisplay;
 
 #[allow(unused)]
 macro_rules! dbg {
@@ -40,16 +45,88 @@ pub fn substrate_sig_to_iroha_sig<T: Trait>(
     }
 }
 
-pub fn iroha_sig_to_substrate_sig<T: Trait>(
-    iroha_crypto::Signature {
-        public_key,
-        signature,
-    }: iroha_crypto::Signature,
-) -> (T::Public, <T as SigningTypes>::Signature) {
-    (
-        <T::Public>::decode(&mut &(*public_key)[..]).unwrap(),
-        <T as SigningTypes>::Signature::decode(&mut &signature[..]).unwrap(),
-    )
+/// Verifies an ed25519 signature made by an Iroha peer or account over `message`.
+pub fn verify_iroha_sig(message: &[u8], sig: &iroha_crypto::Signature) -> bool {
+    let public = match ed25519::Public::try_from(&(*sig.public_key)[..]) {
+        Ok(public) => public,
+        Err(_) => return false,
+    };
+    let signature = ed25519::Signature::from_raw(sig.signature);
+    sp_io::crypto::ed25519_verify(&signature, message, &public)
+}
+
+/// Lowercase hex representation of a hash, without `0x` prefix.
+pub fn hex_hash<H: AsRef<[u8]>>(hash: &H) -> String {
+    format!("{}", HexDisplay::from(&hash.as_ref()))
+}
+
+/// Splits `quantity` into parts fitting into the `u32` quantity of an Iroha instruction.
+pub fn split_quantity(quantity: u128) -> Vec<u32> {
+    let max = u128::from(u32::MAX);
+    let mut parts = Vec::new();
+    let mut remaining = quantity;
+    while remaining > max {
+        parts.push(u32::MAX);
+        remaining -= max;
+    }
+    if remaining > 0 || parts.is_empty() {
+        parts.push(remaining as u32);
+    }
+    parts
+}
+
+/// Byzantine fault tolerant quorum for `n` peers: `n - f` where `f = (n - 1) / 3`, so that any
+/// two quorums share an honest peer. An empty peer set still needs one signature.
+pub fn bft_threshold(n: usize) -> usize {
+    (n - n.saturating_sub(1) / 3).max(1)
+}
+
+/// Checks that `endpoint` is an absolute `http` or `https` URL with a host, an optional numeric
+/// port and an optional path, so request paths can be appended to it.
+pub fn is_valid_iroha_endpoint(endpoint: &[u8]) -> bool {
+    let url = match core::str::from_utf8(endpoint) {
+        Ok(url) => url,
+        Err(_) => return false,
+    };
+    let rest = if url.starts_with("http://") {
+        &url[7..]
+    } else if url.starts_with("https://") {
+        &url[8..]
+    } else {
+        return false;
+    };
+    if rest
+        .chars()
+        .any(|c| !c.is_ascii_graphic() || c == '?' || c == '#')
+    {
+        return false;
+    }
+    let authority = rest.split('/').next().unwrap_or_default();
+    let (host, port) = match authority.rfind(':') {
+        Some(i) => (&authority[..i], Some(&authority[i + 1..])),
+        None => (authority, None),
+    };
+    let port_valid = match port {
+        Some(port) => port.parse::<u16>().is_ok(),
+        None => true,
+    };
+    !host.is_empty() && !host.contains('@') && port_valid
+}
+
+/// Decodes the account id of an SS58 address with a one byte address type, checking the
+/// checksum and that the address type is the chain's `Ss58Prefix`.
+pub fn decode_ss58<T: Trait>(address: &str) -> Option<T::AccountId> {
+    let data = bs58::decode(address).into_vec().ok()?;
+    if data.len() != 35 || data[0] != T::Ss58Prefix::get() {
+        return None;
+    }
+    let mut preimage = b"SS58PRE".to_vec();
+    preimage.extend_from_slice(&data[..33]);
+    let checksum = sp_io::hashing::blake2_512(&preimage);
+    if data[33..] != checksum[..2] {
+        return None;
+    }
+    <T::AccountId>::decode(&mut &data[1..33]).ok()
 }
 
 pub fn substrate_account_id_from_iroha_pk<T: Trait>(
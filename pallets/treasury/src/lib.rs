@@ -8,95 +8,144 @@ mod mock;
 mod tests;
 
 use codec::{Decode, Encode};
-use core::convert::{TryFrom, TryInto};
-use core::marker::PhantomData;
-use frame_support::traits::{Currency, ExistenceRequirement::KeepAlive, ReservableCurrency};
+use core::convert::TryInto;
 use frame_support::{
-    decl_error, decl_event, decl_module, decl_storage, dispatch::DispatchResult, ensure,
+    decl_error, decl_event, decl_module, decl_storage,
+    dispatch::DispatchResult,
+    ensure,
+    traits::{Currency, ExistenceRequirement, Get},
+    Parameter,
 };
 use iroha_client_no_std::prelude::AssetDefinitionId;
+use sp_runtime::traits::{
+    AccountIdConversion, AtLeast32BitUnsigned, CheckedAdd, CheckedSub, MaybeSerializeDeserialize,
+    Member, Saturating, Zero,
+};
 use sp_runtime::ModuleId;
-use sp_std::str::FromStr;
-use system::ensure_signed;
+use sp_std::fmt::Debug;
+use sp_std::prelude::*;
+use system::{ensure_root, ensure_signed};
 
 /// The treasury's module id, used for deriving its sovereign account ID.
-const _MODULE_ID: ModuleId = ModuleId(*b"ily/trsy");
+const MODULE_ID: ModuleId = ModuleId(*b"ily/trsy");
+
+/// Identifier of a registered asset.
+pub type AssetId = u32;
+
+type BalanceOf<T> = <<T as Trait>::Currency as Currency<<T as system::Trait>::AccountId>>::Balance;
 
+/// How the bridge moves an asset between this chain and Iroha.
 #[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq)]
-pub enum AssetKind {
-    XOR,
-    DOT,
-    KSM,
+pub enum AssetMode {
+    /// The asset's home is Iroha: incoming transfers mint it, outgoing transfers burn it.
+    MintBurn,
+    /// The asset's home is this chain: outgoing transfers keep it in the escrow account,
+    /// incoming transfers release it from there.
+    LockRelease,
 }
 
-impl AssetKind {
-    pub fn definition_id(&self) -> AssetDefinitionId {
-        match self {
-            AssetKind::XOR => AssetDefinitionId::new("XOR", "global"),
-            AssetKind::DOT => AssetDefinitionId::new("DOT", "polkadot"),
-            AssetKind::KSM => AssetDefinitionId::new("KSM", "polkadot"),
-        }
-    }
+/// Identifier of the bridge request that caused a lock, unlock or burn.
+pub type RequestId = u64;
+
+/// Registry entry of a bridged asset.
+#[derive(Encode, Decode, Debug, Clone, PartialEq, Eq)]
+pub struct AssetInfo {
+    /// Definition of the asset on the Iroha side.
+    pub definition_id: AssetDefinitionId,
+    pub symbol: Vec<u8>,
+    /// Decimal places of balances on this chain.
+    pub decimals: u8,
+    /// Decimal places of quantities on the Iroha side.
+    pub iroha_decimals: u8,
+    pub mode: AssetMode,
+    /// Disabled assets can't be transferred, minted or locked, but already locked funds can
+    /// still be unlocked or burned.
+    pub enabled: bool,
 }
 
-impl<'a> TryFrom<&'a AssetDefinitionId> for AssetKind {
-    type Error = ();
-
-    fn try_from(asset_def_id: &'a AssetDefinitionId) -> Result<Self, Self::Error> {
-        match asset_def_id {
-            x if x == &AssetDefinitionId::new("XOR", "global") => Ok(AssetKind::XOR),
-            x if x == &AssetDefinitionId::new("DOT", "polkadot") => Ok(AssetKind::DOT),
-            x if x == &AssetDefinitionId::new("KSM", "polkadot") => Ok(AssetKind::KSM),
-            _ => Err(()),
-        }
-    }
+/// Balance of an account in one asset.
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AccountData<Balance> {
+    pub free: Balance,
 }
 
-impl FromStr for AssetKind {
-    type Err = ();
+/// Issued and locked supply of an asset.
+#[derive(Encode, Decode, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AssetSupply {
+    pub issued: u128,
+    /// Locked for outgoing transfers, not yet burned or escrowed.
+    pub locked: u128,
+    /// Kept in the escrow account, backing the supply on Iroha of a lock-and-release asset.
+    pub escrowed: u128,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "XOR" => Ok(AssetKind::XOR),
-            "DOT" => Ok(AssetKind::DOT),
-            "KSM" => Ok(AssetKind::KSM),
-            _ => Err(()),
-        }
+sp_api::decl_runtime_apis! {
+    /// Supply queries, e.g. for comparing the bridged supply with the Iroha bridge account.
+    pub trait TreasuryApi<AccountId> where AccountId: codec::Codec {
+        /// Supply of an asset, `None` if it isn't registered.
+        fn asset_supply(asset_id: AssetId) -> Option<AssetSupply>;
+        /// The account holding all locked funds.
+        fn escrow_account() -> AccountId;
     }
 }
 
 /// The pallet's configuration trait.
-/// Instantiation of this pallet requires the existence of a module that
-/// implements Currency and ReservableCurrency. The Balances module can be used
-/// for this. The Balances module then gives functions for total supply, balances
-/// of accounts, and any function defined by the Currency and ReservableCurrency
-/// traits.
+/// Balances of all registered assets are kept by this pallet, so bridging a new asset
+/// only requires registering it.
 pub trait Trait: system::Trait {
-    type XOR: Currency<<Self as system::Trait>::AccountId>
-        + ReservableCurrency<<Self as system::Trait>::AccountId>;
-    type DOT: Currency<<Self as system::Trait>::AccountId>
-        + ReservableCurrency<<Self as system::Trait>::AccountId>;
-    type KSM: Currency<<Self as system::Trait>::AccountId>
-        + ReservableCurrency<<Self as system::Trait>::AccountId>;
+    /// The balance of an account in any of the registered assets.
+    type Balance: Parameter
+        + Member
+        + AtLeast32BitUnsigned
+        + Default
+        + Copy
+        + MaybeSerializeDeserialize
+        + Debug;
+
+    /// The native currency of the chain.
+    type Currency: Currency<Self::AccountId>;
+
+    /// The registered asset whose balances are kept by `Currency` instead of this pallet.
+    type NativeAssetId: Get<AssetId>;
 
     /// The overarching event type.
     type Event: From<Event<Self>> + Into<<Self as system::Trait>::Event>;
 }
 
-pub fn balance_to_num<T: Trait, C: Currency<<T as system::Trait>::AccountId>>(
-    amount: C::Balance,
-) -> Result<u128, Error<T>> {
+/// Converts a balance to the `u128` amount used by the bridge.
+pub fn balance_to_num<T: Trait>(amount: T::Balance) -> Result<u128, Error<T>> {
     amount
-        .try_into()
-        .map_err(|_| <Error<T>>::InvalidBalanceType)?
         .try_into()
         .map_err(|_| <Error<T>>::InvalidBalanceType)
 }
 
-pub fn num_to_balance<T: Trait, C: Currency<<T as system::Trait>::AccountId>>(
-    amount_num: u128,
-) -> Result<C::Balance, Error<T>> {
-    C::Balance::try_from(usize::try_from(amount_num).map_err(|_| <Error<T>>::InvalidBalanceType)?)
+/// Converts `amount` with `from_decimals` decimal places to `to_decimals` decimal places.
+/// Scaling down rounds towards zero, so it fails with `PrecisionLoss` unless the dropped
+/// digits are all zero.
+pub fn convert_decimals<T: Trait>(
+    amount: u128,
+    from_decimals: u8,
+    to_decimals: u8,
+) -> Result<u128, Error<T>> {
+    if to_decimals >= from_decimals {
+        10u128
+            .checked_pow(u32::from(to_decimals - from_decimals))
+            .and_then(|factor| amount.checked_mul(factor))
+            .ok_or(<Error<T>>::Overflow)
+    } else {
+        match 10u128.checked_pow(u32::from(from_decimals - to_decimals)) {
+            Some(factor) if amount % factor == 0 => Ok(amount / factor),
+            // the factor exceeds any `u128`, so only zero converts exactly
+            None if amount == 0 => Ok(0),
+            _ => Err(<Error<T>>::PrecisionLoss),
+        }
+    }
+}
+
+/// Converts a `u128` amount used by the bridge to a balance, failing if it doesn't fit.
+pub fn num_to_balance<T: Trait>(amount_num: u128) -> Result<T::Balance, Error<T>> {
+    amount_num
+        .try_into()
         .map_err(|_| <Error<T>>::InvalidBalanceType)
 }
 
@@ -106,6 +155,16 @@ decl_error! {
         InsufficientFunds,
         InvalidBalanceType,
         InvalidAssetName,
+        UnknownAsset,
+        AssetDisabled,
+        AssetAlreadyRegistered,
+        Overflow,
+        Underflow,
+        PrecisionLoss,
+        InvalidAssetMode,
+        InsufficientEscrowedFunds,
+        /// A native transfer would create an account with less than the existential deposit.
+        BelowExistentialDeposit,
     }
 }
 
@@ -113,11 +172,59 @@ decl_error! {
 decl_storage! {
     trait Store for Module<T: Trait> as Treasury {
         /// ## Storage
-        /// Note that account's balances and locked balances are handled
-        /// through the Balances module.
-        ///
-        /// Total locked PolkaDOT
-        TotalLocked: u128;
+        /// Registered assets.
+        Assets get(fn asset_info): map hasher(twox_64_concat) AssetId => Option<AssetInfo>;
+        /// Registered asset of an Iroha asset definition.
+        AssetByDefinition get(fn asset_by_definition): map hasher(blake2_128_concat) AssetDefinitionId => Option<AssetId>;
+        /// Balances of accounts per asset.
+        Account get(fn account): double_map hasher(twox_64_concat) AssetId, hasher(blake2_128_concat) T::AccountId => AccountData<T::Balance>;
+        /// Funds of an account held by the escrow account per asset.
+        Locked get(fn locked): double_map hasher(twox_64_concat) AssetId, hasher(blake2_128_concat) T::AccountId => T::Balance;
+        /// Total issuance per asset.
+        TotalIssuance get(fn total_issuance): map hasher(twox_64_concat) AssetId => T::Balance;
+        /// Total locked funds per asset.
+        TotalLocked get(fn total_locked): map hasher(twox_64_concat) AssetId => T::Balance;
+        /// Total funds kept in the escrow account per lock-and-release asset.
+        TotalEscrowed get(fn total_escrowed): map hasher(twox_64_concat) AssetId => T::Balance;
+    }
+    add_extra_genesis {
+        /// Assets as `(id, symbol, Iroha domain, decimals, Iroha decimals)`. The Iroha asset
+        /// definition is `symbol#domain`. The native asset is lock-and-release, all others are
+        /// mint-and-burn. The escrow account is endowed with the existential deposit of the
+        /// native asset, so that it exists before the first lock and is never reaped. The
+        /// currency's genesis has to be built before this one.
+        config(assets): Vec<(AssetId, Vec<u8>, Vec<u8>, u8, u8)>;
+        config(balances): Vec<(AssetId, T::AccountId, T::Balance)>;
+        build(|config: &GenesisConfig<T>| {
+            for (asset_id, symbol, domain, decimals, iroha_decimals) in &config.assets {
+                let definition_id = AssetDefinitionId::new(
+                    sp_std::str::from_utf8(symbol).expect("asset symbol is valid UTF-8"),
+                    sp_std::str::from_utf8(domain).expect("asset domain is valid UTF-8"),
+                );
+                <Module<T>>::insert_asset(*asset_id, AssetInfo {
+                    definition_id,
+                    symbol: symbol.clone(),
+                    decimals: *decimals,
+                    iroha_decimals: *iroha_decimals,
+                    mode: if *asset_id == T::NativeAssetId::get() {
+                        AssetMode::LockRelease
+                    } else {
+                        AssetMode::MintBurn
+                    },
+                    enabled: true,
+                });
+            }
+            if Assets::contains_key(T::NativeAssetId::get()) {
+                let escrow = <Module<T>>::escrow_account();
+                let _ = T::Currency::deposit_creating(&escrow, T::Currency::minimum_balance());
+            }
+            for (asset_id, who, amount) in &config.balances {
+                assert!(Assets::contains_key(asset_id), "endowed asset is registered");
+                assert!(*asset_id != T::NativeAssetId::get(), "native asset is endowed by the currency");
+                <Account<T>>::mutate(asset_id, who, |data| data.free += *amount);
+                <TotalIssuance<T>>::mutate(asset_id, |total| *total += *amount);
+            }
+        });
     }
 }
 
@@ -127,11 +234,20 @@ decl_event!(
     where
         AccountId = <T as system::Trait>::AccountId,
     {
-        Transfer(AccountId, AccountId, u128),
-        Mint(AccountId, u128),
-        Lock(AccountId, u128),
-        Unlock(AccountId, u128),
-        Burn(AccountId, u128),
+        Transfer(AccountId, AccountId, AssetId, u128),
+        Mint(AccountId, AssetId, u128),
+        /// Funds were locked, by the given bridge request if any.
+        Lock(AccountId, AssetId, u128, Option<RequestId>),
+        /// Locked funds were released, by the given bridge request if any.
+        Unlock(AccountId, AssetId, u128, Option<RequestId>),
+        /// Locked funds were burned, by the given bridge request if any.
+        Burn(AccountId, AssetId, u128, Option<RequestId>),
+        /// Locked funds were kept in the escrow account, by the given bridge request if any.
+        Escrow(AccountId, AssetId, u128, Option<RequestId>),
+        /// Escrowed funds were released to an account.
+        Release(AccountId, AssetId, u128),
+        AssetRegistered(AssetId, AssetDefinitionId),
+        AssetEnabled(AssetId, bool),
     }
 );
 
@@ -143,216 +259,401 @@ decl_module! {
         // this is needed only if you are using events in your pallet
         fn deposit_event() = default;
 
-        /// Transfer an amount of XOR (without fees)
+        /// Transfer an amount of an asset (without fees)
         ///
         /// # Arguments
         ///
         /// * `origin` - sender of the transaction
+        /// * `asset_id` - the transferred asset
         /// * `receiver` - receiver of the transaction
-        /// * `amount` - amount of XOR
+        /// * `amount` - amount of the asset
         #[weight = 1000]
-        fn transfer(origin, asset_kind: AssetKind, receiver: T::AccountId, amount_num: u128)
+        fn transfer(origin, asset_id: AssetId, receiver: T::AccountId, amount_num: u128)
             -> DispatchResult
         {
             let sender = ensure_signed(origin)?;
+            Self::ensure_enabled(asset_id)?;
+            let amount = num_to_balance::<T>(amount_num)?;
+
+            Self::move_free(asset_id, &sender, &receiver, amount)?;
 
-            match asset_kind {
-                AssetKind::XOR => {
-                    let amount = <T::XOR as Currency<_>>::Balance::try_from(usize::try_from(amount_num).map_err(|_| <Error<T>>::InvalidBalanceType)?).map_err(|_| <Error<T>>::InvalidBalanceType)?;
-                    T::XOR::transfer(&sender, &receiver, amount, KeepAlive)
-                }
-                AssetKind::DOT => {
-                    let amount = <T::DOT as Currency<_>>::Balance::try_from(usize::try_from(amount_num).map_err(|_| <Error<T>>::InvalidBalanceType)?).map_err(|_| <Error<T>>::InvalidBalanceType)?;
-                    T::DOT::transfer(&sender, &receiver, amount, KeepAlive)
-                }
-                AssetKind::KSM => {
-                    let amount = num_to_balance::<T, T::KSM>(amount_num)?; // <T::KSM as Currency<_>>::Balance::try_from(usize::try_from(amount_num).map_err(|_| <Error<T>>::InvalidBalanceType)?).map_err(|_| <Error<T>>::InvalidBalanceType)?;
-                    T::KSM::transfer(&sender, &receiver, amount, KeepAlive)
-                }
-            }.map_err(|_| <Error<T>>::InsufficientFunds)?;
-
-            Self::deposit_event(RawEvent::Transfer(sender, receiver, amount_num));
+            Self::deposit_event(RawEvent::Transfer(sender, receiver, asset_id, amount_num));
 
             Ok(())
         }
+
+        /// Registers an asset bridged from the Iroha asset definition `definition_id`.
+        /// The native asset can only be registered as lock-and-release.
+        #[weight = 0]
+        pub fn register_asset(origin, asset_id: AssetId, definition_id: AssetDefinitionId, symbol: Vec<u8>, decimals: u8, iroha_decimals: u8, mode: AssetMode) -> DispatchResult {
+            ensure_root(origin)?;
+            ensure!(!Assets::contains_key(asset_id), <Error<T>>::AssetAlreadyRegistered);
+            ensure!(!AssetByDefinition::contains_key(&definition_id), <Error<T>>::AssetAlreadyRegistered);
+            ensure!(asset_id != T::NativeAssetId::get() || mode == AssetMode::LockRelease, <Error<T>>::InvalidAssetMode);
+            Self::insert_asset(asset_id, AssetInfo {
+                definition_id: definition_id.clone(),
+                symbol,
+                decimals,
+                iroha_decimals,
+                mode,
+                enabled: true,
+            });
+            Self::deposit_event(RawEvent::AssetRegistered(asset_id, definition_id));
+            Ok(())
+        }
+
+        /// Enables or disables transfers of a registered asset.
+        #[weight = 0]
+        pub fn set_asset_enabled(origin, asset_id: AssetId, enabled: bool) -> DispatchResult {
+            ensure_root(origin)?;
+            let mut info = Self::asset_info(asset_id).ok_or(<Error<T>>::UnknownAsset)?;
+            info.enabled = enabled;
+            Assets::insert(asset_id, info);
+            Self::deposit_event(RawEvent::AssetEnabled(asset_id, enabled));
+            Ok(())
+        }
     }
 }
 
-#[derive(Encode, Decode, Clone, PartialEq, Eq)]
-pub struct Asset<T, C> {
-    _t: PhantomData<T>,
-    _c: PhantomData<C>,
-}
+impl<T: Trait> Module<T> {
+    fn insert_asset(asset_id: AssetId, info: AssetInfo) {
+        AssetByDefinition::insert(&info.definition_id, asset_id);
+        Assets::insert(asset_id, info);
+    }
 
-impl<T, C> Asset<T, C>
-where
-    T: Trait,
-    C: Currency<T::AccountId> + ReservableCurrency<T::AccountId>,
-{
-    /// Total supply of XOR
-    #[allow(unused)]
-    pub fn get_total_supply() -> C::Balance {
-        C::total_issuance()
+    /// Registered asset of an Iroha asset definition
+    pub fn asset_id(definition_id: &AssetDefinitionId) -> Result<AssetId, Error<T>> {
+        Self::asset_by_definition(definition_id).ok_or(<Error<T>>::UnknownAsset)
     }
-    /// Balance of an account (wrapper)
-    #[allow(unused)]
-    pub fn get_balance_from_account(account: T::AccountId) -> C::Balance {
-        C::free_balance(&account)
+
+    /// Iroha asset definition of a registered asset
+    pub fn definition_id(asset_id: AssetId) -> Result<AssetDefinitionId, Error<T>> {
+        Self::asset_info(asset_id)
+            .map(|info| info.definition_id)
+            .ok_or(<Error<T>>::UnknownAsset)
     }
-    /// Locked balance of an account (wrapper)
-    #[allow(unused)]
-    pub fn get_locked_balance_from_account(account: T::AccountId) -> C::Balance {
-        C::reserved_balance(&account)
+
+    /// Converts an Iroha quantity of a registered asset to an amount on this chain
+    pub fn from_iroha_quantity(asset_id: AssetId, quantity: u128) -> Result<u128, Error<T>> {
+        let info = Self::asset_info(asset_id).ok_or(<Error<T>>::UnknownAsset)?;
+        convert_decimals::<T>(quantity, info.iroha_decimals, info.decimals)
+    }
+
+    /// Converts an amount of a registered asset on this chain to an Iroha quantity
+    pub fn to_iroha_quantity(asset_id: AssetId, amount: u128) -> Result<u128, Error<T>> {
+        let info = Self::asset_info(asset_id).ok_or(<Error<T>>::UnknownAsset)?;
+        convert_decimals::<T>(amount, info.decimals, info.iroha_decimals)
     }
-    /// Increase the supply of locked XOR
-    #[allow(unused)]
-    pub fn increase_total_locked(amount_num: u128) -> Result<(), Error<T>> {
-        let new_locked = TotalLocked::get() + amount_num;
-        TotalLocked::put(new_locked);
+
+    fn ensure_enabled(asset_id: AssetId) -> Result<(), Error<T>> {
+        let info = Self::asset_info(asset_id).ok_or(<Error<T>>::UnknownAsset)?;
+        ensure!(info.enabled, <Error<T>>::AssetDisabled);
+        Ok(())
+    }
+
+    /// How the bridge moves a registered asset
+    pub fn asset_mode(asset_id: AssetId) -> Result<AssetMode, Error<T>> {
+        Self::asset_info(asset_id)
+            .map(|info| info.mode)
+            .ok_or(<Error<T>>::UnknownAsset)
+    }
+
+    fn ensure_mode(asset_id: AssetId, mode: AssetMode) -> Result<(), Error<T>> {
+        ensure!(
+            Self::asset_mode(asset_id)? == mode,
+            <Error<T>>::InvalidAssetMode
+        );
+        Ok(())
+    }
+
+    /// Checks that an incoming transfer of `amount` can be minted or released
+    pub fn ensure_can_credit(asset_id: AssetId, amount_num: u128) -> Result<(), Error<T>> {
+        Self::ensure_enabled(asset_id)?;
+        let amount = num_to_balance::<T>(amount_num)?;
+        if Self::asset_mode(asset_id)? == AssetMode::LockRelease {
+            ensure!(
+                Self::total_escrowed(asset_id) >= amount,
+                <Error<T>>::InsufficientEscrowedFunds
+            );
+        }
         Ok(())
     }
-    /// Decrease the supply of locked XOR
-    #[allow(unused)]
-    pub fn decrease_total_locked(amount_num: u128) -> Result<(), Error<T>> {
-        let new_locked = TotalLocked::get() - amount_num;
-        TotalLocked::put(new_locked);
+
+    fn currency_to_num(amount: BalanceOf<T>) -> Result<u128, Error<T>> {
+        amount
+            .try_into()
+            .map_err(|_| <Error<T>>::InvalidBalanceType)
+    }
+
+    /// Total supply of an asset
+    pub fn get_total_supply(asset_id: AssetId) -> Result<u128, Error<T>> {
+        if asset_id == T::NativeAssetId::get() {
+            return Self::currency_to_num(T::Currency::total_issuance());
+        }
+        balance_to_num::<T>(Self::total_issuance(asset_id))
+    }
+
+    /// Balance of an account
+    pub fn get_balance_from_account(
+        account: T::AccountId,
+        asset_id: AssetId,
+    ) -> Result<u128, Error<T>> {
+        if asset_id == T::NativeAssetId::get() {
+            return Self::currency_to_num(T::Currency::free_balance(&account));
+        }
+        balance_to_num::<T>(Self::account(asset_id, account).free)
+    }
+
+    /// Moves free funds between two accounts, through `Currency` for the native asset.
+    fn move_free(
+        asset_id: AssetId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: T::Balance,
+    ) -> Result<(), Error<T>> {
+        if asset_id == T::NativeAssetId::get() {
+            let amount: BalanceOf<T> = balance_to_num::<T>(amount)?
+                .try_into()
+                .map_err(|_| <Error<T>>::InvalidBalanceType)?;
+            ensure!(
+                amount >= T::Currency::minimum_balance()
+                    || !T::Currency::total_balance(to).is_zero(),
+                <Error<T>>::BelowExistentialDeposit
+            );
+            // the escrow account keeps its endowment
+            let existence = if *from == Self::escrow_account() {
+                ExistenceRequirement::KeepAlive
+            } else {
+                ExistenceRequirement::AllowDeath
+            };
+            return T::Currency::transfer(from, to, amount, existence)
+                .map_err(|_| <Error<T>>::InsufficientFunds);
+        }
+        let mut from_data = Self::account(asset_id, from);
+        from_data.free = from_data
+            .free
+            .checked_sub(&amount)
+            .ok_or(<Error<T>>::InsufficientFunds)?;
+        if from == to {
+            return Ok(());
+        }
+        let mut to_data = Self::account(asset_id, to);
+        to_data.free = to_data
+            .free
+            .checked_add(&amount)
+            .ok_or(<Error<T>>::Overflow)?;
+        <Account<T>>::insert(asset_id, from, from_data);
+        <Account<T>>::insert(asset_id, to, to_data);
         Ok(())
     }
+
+    /// Locked balance of an account
+    pub fn get_locked_balance_from_account(
+        account: T::AccountId,
+        asset_id: AssetId,
+    ) -> Result<u128, Error<T>> {
+        balance_to_num::<T>(Self::locked(asset_id, account))
+    }
+
+    /// The sovereign account holding locked funds until they are unlocked or burned
+    pub fn escrow_account() -> T::AccountId {
+        MODULE_ID.into_account()
+    }
+
+    /// Issued, locked and escrowed supply of an asset
+    pub fn asset_supply(asset_id: AssetId) -> Option<AssetSupply> {
+        Self::asset_info(asset_id)?;
+        Some(AssetSupply {
+            issued: Self::get_total_supply(asset_id).ok()?,
+            locked: balance_to_num::<T>(Self::total_locked(asset_id)).ok()?,
+            escrowed: balance_to_num::<T>(Self::total_escrowed(asset_id)).ok()?,
+        })
+    }
+
     /// Mint new tokens
     ///
     /// # Arguments
     ///
-    /// * `requester` - XOR user requesting new tokens
-    /// * `amount` - to be issued amount of XOR
-    #[allow(unused)]
-    pub fn mint(requester: T::AccountId, amount_num: u128) -> Result<(), Error<T>> {
+    /// * `requester` - user requesting new tokens
+    /// * `asset_id` - the minted asset
+    /// * `amount` - to be issued amount of the asset
+    pub fn mint(
+        requester: T::AccountId,
+        asset_id: AssetId,
+        amount_num: u128,
+    ) -> Result<(), Error<T>> {
+        Self::ensure_enabled(asset_id)?;
+        Self::ensure_mode(asset_id, AssetMode::MintBurn)?;
+        let amount = num_to_balance::<T>(amount_num)?;
         // adds the amount to the total balance of tokens
-        let amount = num_to_balance::<T, C>(amount_num)?;
-        let minted_tokens = C::issue(amount);
+        let total_issuance = Self::total_issuance(asset_id)
+            .checked_add(&amount)
+            .ok_or(<Error<T>>::Overflow)?;
         // adds the added amount to the requester's balance
-        C::resolve_creating(&requester, minted_tokens);
+        let mut data = Self::account(asset_id, &requester);
+        data.free = data.free.checked_add(&amount).ok_or(<Error<T>>::Overflow)?;
+        <TotalIssuance<T>>::insert(asset_id, total_issuance);
+        <Account<T>>::insert(asset_id, &requester, data);
 
-        Module::<T>::deposit_event(RawEvent::Mint(requester, amount_num));
+        Self::deposit_event(RawEvent::Mint(requester, asset_id, amount_num));
         Ok(())
     }
 
-    /// Lock XOR tokens to burn them. Note: this removes them from the
-    /// free balance of XOR and adds them to the locked supply of XOR.
+    /// Lock tokens to burn or escrow them. Note: this moves them from the
+    /// free balance to the escrow account and adds them to the locked supply.
     ///
     /// # Arguments
     ///
     /// * `redeemer` - the account redeeming tokens
-    /// * `amount` - to be locked amount of XOR
-    #[allow(unused)]
-    pub fn lock(redeemer: T::AccountId, amount_num: u128) -> Result<(), Error<T>> {
-        let amount = num_to_balance::<T, C>(amount_num)?;
-        C::reserve(&redeemer, amount).map_err(|_| <Error<T>>::InsufficientFunds)?;
-
+    /// * `asset_id` - the locked asset
+    /// * `amount` - to be locked amount of the asset
+    /// * `request_id` - the bridge request locking the tokens, if any
+    pub fn lock(
+        redeemer: T::AccountId,
+        asset_id: AssetId,
+        amount_num: u128,
+        request_id: Option<RequestId>,
+    ) -> Result<(), Error<T>> {
+        Self::ensure_enabled(asset_id)?;
+        let amount = num_to_balance::<T>(amount_num)?;
+        let locked = Self::locked(asset_id, &redeemer)
+            .checked_add(&amount)
+            .ok_or(<Error<T>>::Overflow)?;
+        let total_locked = Self::total_locked(asset_id)
+            .checked_add(&amount)
+            .ok_or(<Error<T>>::Overflow)?;
+
+        Self::move_free(asset_id, &redeemer, &Self::escrow_account(), amount)?;
         // update total locked balance
-        Self::increase_total_locked(amount_num)?;
-        Module::<T>::deposit_event(RawEvent::Lock(redeemer, amount_num));
+        <Locked<T>>::insert(asset_id, &redeemer, locked);
+        <TotalLocked<T>>::insert(asset_id, total_locked);
+        Self::deposit_event(RawEvent::Lock(redeemer, asset_id, amount_num, request_id));
         Ok(())
     }
 
-    #[allow(unused)]
-    pub fn unlock(redeemer: T::AccountId, amount_num: u128) -> Result<(), Error<T>> {
-        let amount = num_to_balance::<T, C>(amount_num)?;
-        C::unreserve(&redeemer, amount);
+    /// Unlock previously locked tokens, returning them from the escrow account
+    ///
+    /// # Arguments
+    ///
+    /// * `redeemer` - the account the tokens were locked for
+    /// * `asset_id` - the unlocked asset
+    /// * `amount` - the to be unlocked amount of the asset
+    /// * `request_id` - the bridge request unlocking the tokens, if any
+    pub fn unlock(
+        redeemer: T::AccountId,
+        asset_id: AssetId,
+        amount_num: u128,
+        request_id: Option<RequestId>,
+    ) -> Result<(), Error<T>> {
+        let amount = num_to_balance::<T>(amount_num)?;
+        let (locked, total_locked) = Self::checked_unlocked(&redeemer, asset_id, amount)?;
 
-        // update total locked balance
-        Self::decrease_total_locked(amount_num)?;
-        Module::<T>::deposit_event(RawEvent::Unlock(redeemer, amount_num));
+        Self::move_free(asset_id, &Self::escrow_account(), &redeemer, amount)?;
+        <Locked<T>>::insert(asset_id, &redeemer, locked);
+        <TotalLocked<T>>::insert(asset_id, total_locked);
+        Self::deposit_event(RawEvent::Unlock(redeemer, asset_id, amount_num, request_id));
         Ok(())
     }
 
-    /// Burn previously locked XOR tokens
+    /// Burn previously locked tokens
     ///
     /// # Arguments
     ///
     /// * `redeemer` - the account redeeming tokens
-    /// * `amount` - the to be burned amount of XOR
-    #[allow(unused)]
-    pub fn burn(redeemer: T::AccountId, amount_num: u128) -> Result<(), Error<T>> {
-        let amount = num_to_balance::<T, C>(amount_num)?;
-        ensure!(
-            C::reserved_balance(&redeemer) >= amount,
-            <Error<T>>::InsufficientLockedFunds
-        );
+    /// * `asset_id` - the burned asset
+    /// * `amount` - the to be burned amount of the asset
+    /// * `request_id` - the bridge request burning the tokens, if any
+    pub fn burn(
+        redeemer: T::AccountId,
+        asset_id: AssetId,
+        amount_num: u128,
+        request_id: Option<RequestId>,
+    ) -> Result<(), Error<T>> {
+        Self::ensure_mode(asset_id, AssetMode::MintBurn)?;
+        let amount = num_to_balance::<T>(amount_num)?;
+        let (locked, total_locked) = Self::checked_unlocked(&redeemer, asset_id, amount)?;
+        let escrow = Self::escrow_account();
+        let mut escrow_data = Self::account(asset_id, &escrow);
+        escrow_data.free = escrow_data
+            .free
+            .checked_sub(&amount)
+            .ok_or(<Error<T>>::InsufficientLockedFunds)?;
 
         // burn the tokens from the locked balance
-        Self::decrease_total_locked(amount_num)?;
-        // burn the tokens for the redeemer
-        // remainder should always be 0 and is checked above
-        let (_burned_tokens, _remainder) = C::slash_reserved(&redeemer, amount);
+        <Locked<T>>::insert(asset_id, &redeemer, locked);
+        <TotalLocked<T>>::insert(asset_id, total_locked);
+        <Account<T>>::insert(asset_id, &escrow, escrow_data);
+        <TotalIssuance<T>>::mutate(asset_id, |total| *total = total.saturating_sub(amount));
 
-        Module::<T>::deposit_event(RawEvent::Burn(redeemer, amount_num));
+        Self::deposit_event(RawEvent::Burn(redeemer, asset_id, amount_num, request_id));
         Ok(())
     }
-}
 
-impl<T: Trait> Module<T> {
-    pub fn mint(
-        requester: T::AccountId,
-        asset_kind: AssetKind,
-        amount_num: u128,
-    ) -> Result<(), Error<T>> {
-        match asset_kind {
-            AssetKind::XOR => Asset::<T, T::XOR>::mint(requester, amount_num),
-            AssetKind::DOT => Asset::<T, T::DOT>::mint(requester, amount_num),
-            AssetKind::KSM => Asset::<T, T::KSM>::mint(requester, amount_num),
-        }
-    }
-
-    pub fn lock(
-        redeemer: T::AccountId,
-        asset_kind: AssetKind,
-        amount_num: u128,
-    ) -> Result<(), Error<T>> {
-        match asset_kind {
-            AssetKind::XOR => Asset::<T, T::XOR>::lock(redeemer, amount_num),
-            AssetKind::DOT => Asset::<T, T::DOT>::lock(redeemer, amount_num),
-            AssetKind::KSM => Asset::<T, T::KSM>::lock(redeemer, amount_num),
-        }
-    }
-
-    pub fn unlock(
+    /// Keep previously locked tokens in the escrow account, backing the asset on Iroha
+    ///
+    /// # Arguments
+    ///
+    /// * `redeemer` - the account redeeming tokens
+    /// * `asset_id` - the escrowed lock-and-release asset
+    /// * `amount` - the to be escrowed amount of the asset
+    /// * `request_id` - the bridge request escrowing the tokens, if any
+    pub fn escrow(
         redeemer: T::AccountId,
-        asset_kind: AssetKind,
+        asset_id: AssetId,
         amount_num: u128,
+        request_id: Option<RequestId>,
     ) -> Result<(), Error<T>> {
-        match asset_kind {
-            AssetKind::XOR => Asset::<T, T::XOR>::unlock(redeemer, amount_num),
-            AssetKind::DOT => Asset::<T, T::DOT>::unlock(redeemer, amount_num),
-            AssetKind::KSM => Asset::<T, T::KSM>::unlock(redeemer, amount_num),
-        }
+        Self::ensure_mode(asset_id, AssetMode::LockRelease)?;
+        let amount = num_to_balance::<T>(amount_num)?;
+        let (locked, total_locked) = Self::checked_unlocked(&redeemer, asset_id, amount)?;
+        let total_escrowed = Self::total_escrowed(asset_id)
+            .checked_add(&amount)
+            .ok_or(<Error<T>>::Overflow)?;
+
+        <Locked<T>>::insert(asset_id, &redeemer, locked);
+        <TotalLocked<T>>::insert(asset_id, total_locked);
+        <TotalEscrowed<T>>::insert(asset_id, total_escrowed);
+        Self::deposit_event(RawEvent::Escrow(redeemer, asset_id, amount_num, request_id));
+        Ok(())
     }
 
-    pub fn burn(
-        redeemer: T::AccountId,
-        asset_kind: AssetKind,
+    /// Release escrowed tokens to an account
+    ///
+    /// # Arguments
+    ///
+    /// * `receiver` - the account receiving tokens
+    /// * `asset_id` - the released lock-and-release asset
+    /// * `amount` - the to be released amount of the asset
+    pub fn release(
+        receiver: T::AccountId,
+        asset_id: AssetId,
         amount_num: u128,
     ) -> Result<(), Error<T>> {
-        match asset_kind {
-            AssetKind::XOR => Asset::<T, T::XOR>::burn(redeemer, amount_num),
-            AssetKind::DOT => Asset::<T, T::DOT>::burn(redeemer, amount_num),
-            AssetKind::KSM => Asset::<T, T::KSM>::burn(redeemer, amount_num),
-        }
+        Self::ensure_enabled(asset_id)?;
+        Self::ensure_mode(asset_id, AssetMode::LockRelease)?;
+        let amount = num_to_balance::<T>(amount_num)?;
+        let total_escrowed = Self::total_escrowed(asset_id)
+            .checked_sub(&amount)
+            .ok_or(<Error<T>>::InsufficientEscrowedFunds)?;
+
+        Self::move_free(asset_id, &Self::escrow_account(), &receiver, amount)?;
+        <TotalEscrowed<T>>::insert(asset_id, total_escrowed);
+        Self::deposit_event(RawEvent::Release(receiver, asset_id, amount_num));
+        Ok(())
     }
 
-    pub fn get_balance_from_account(
-        account: T::AccountId,
-        asset_kind: AssetKind,
-    ) -> Result<u128, Error<T>> {
-        match asset_kind {
-            AssetKind::XOR => {
-                balance_to_num::<T, T::XOR>(Asset::<T, T::XOR>::get_balance_from_account(account))
-            }
-            AssetKind::DOT => {
-                balance_to_num::<T, T::DOT>(Asset::<T, T::DOT>::get_balance_from_account(account))
-            }
-            AssetKind::KSM => {
-                balance_to_num::<T, T::KSM>(Asset::<T, T::KSM>::get_balance_from_account(account))
-            }
-        }
+    /// Locked balance of `redeemer` and total locked supply after taking out `amount`.
+    fn checked_unlocked(
+        redeemer: &T::AccountId,
+        asset_id: AssetId,
+        amount: T::Balance,
+    ) -> Result<(T::Balance, T::Balance), Error<T>> {
+        let locked = Self::locked(asset_id, redeemer)
+            .checked_sub(&amount)
+            .ok_or(<Error<T>>::InsufficientLockedFunds)?;
+        let total_locked = Self::total_locked(asset_id)
+            .checked_sub(&amount)
+            .ok_or(<Error<T>>::Underflow)?;
+        Ok((locked, total_locked))
     }
 }
@@ -1,5 +1,5 @@
 /// Mocking the test environment
-use crate::{Module, Trait};
+use crate::{AssetId, GenesisConfig, Module, Trait};
 use frame_support::{
     impl_outer_event, impl_outer_origin, parameter_types,
     weights::{
@@ -7,7 +7,6 @@ use frame_support::{
         Weight,
     },
 };
-use pallet_balances as balances;
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
@@ -26,8 +25,8 @@ mod test_events {
 impl_outer_event! {
     pub enum TestEvent for Test {
         system<T>,
+        pallet_balances<T>,
         test_events<T>,
-        balances<T>,
     }
 }
 
@@ -47,9 +46,12 @@ parameter_types! {
     pub const MaximumBlockWeight: Weight = 1024;
     pub const MaximumBlockLength: u32 = 2 * 1024;
     pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
+    pub const ExistentialDeposit: Balance = 10;
+    pub const NativeAssetId: AssetId = NATIVE;
 }
 
 impl system::Trait for Test {
+    type BaseCallFilter = ();
     type AccountId = AccountId;
     type Call = ();
     type Lookup = IdentityLookup<Self::AccountId>;
@@ -62,6 +64,7 @@ impl system::Trait for Test {
     type Origin = Origin;
     type BlockHashCount = BlockHashCount;
     type MaximumBlockWeight = MaximumBlockWeight;
+    type MaximumExtrinsicWeight = MaximumBlockWeight;
     type MaximumBlockLength = MaximumBlockLength;
     type AvailableBlockRatio = AvailableBlockRatio;
     type BlockExecutionWeight = BlockExecutionWeight;
@@ -71,12 +74,9 @@ impl system::Trait for Test {
     type ModuleToIndex = ();
     type OnNewAccount = ();
     type OnKilledAccount = ();
-    type AccountData = pallet_balances::AccountData<u64>;
+    type AccountData = pallet_balances::AccountData<Balance>;
 }
 
-parameter_types! {
-    pub const ExistentialDeposit: u64 = 1;
-}
 impl pallet_balances::Trait for Test {
     type Balance = Balance;
     type Event = TestEvent;
@@ -86,11 +86,13 @@ impl pallet_balances::Trait for Test {
 }
 
 impl Trait for Test {
-    type XOR = Balances;
+    type Balance = Balance;
+    type Currency = Balances;
+    type NativeAssetId = NativeAssetId;
     type Event = TestEvent;
 }
 
-pub type Error = crate::Error;
+pub type Error = crate::Error<Test>;
 
 pub type System = system::Module<Test>;
 pub type Balances = pallet_balances::Module<Test>;
@@ -98,8 +100,12 @@ pub type Treasury = Module<Test>;
 
 pub const ALICE: AccountId = 1;
 pub const BOB: AccountId = 2;
-pub const ALICE_BALANCE: u64 = 1_000_000;
-pub const BOB_BALANCE: u64 = 1_000_000;
+pub const ALICE_BALANCE: u128 = 1_000_000;
+pub const BOB_BALANCE: u128 = 1_000_000;
+
+pub const XOR: AssetId = 0;
+pub const DOT: AssetId = 1;
+pub const NATIVE: AssetId = 4;
 
 pub struct ExtBuilder;
 
@@ -110,7 +116,21 @@ impl ExtBuilder {
             .unwrap();
 
         pallet_balances::GenesisConfig::<Test> {
-            balances: vec![(ALICE, ALICE_BALANCE), (BOB, BOB_BALANCE)],
+            balances: vec![(ALICE, ALICE_BALANCE as Balance)],
+        }
+        .assimilate_storage(&mut storage)
+        .unwrap();
+
+        GenesisConfig::<Test> {
+            assets: vec![
+                (XOR, b"XOR".to_vec(), b"global".to_vec(), 0, 0),
+                (DOT, b"DOT".to_vec(), b"polkadot".to_vec(), 10, 4),
+                (NATIVE, b"UNIT".to_vec(), b"polkadot".to_vec(), 0, 0),
+            ],
+            balances: vec![
+                (XOR, ALICE, ALICE_BALANCE as Balance),
+                (XOR, BOB, BOB_BALANCE as Balance),
+            ],
         }
         .assimilate_storage(&mut storage)
         .unwrap();
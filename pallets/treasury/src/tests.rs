@@ -1,7 +1,12 @@
 /// Tests for Treasury
 use crate::mock::*;
-use crate::RawEvent;
-use frame_support::{assert_err, assert_ok};
+use crate::{
+    balance_to_num, convert_decimals, num_to_balance, AssetMode, AssetSupply, RawEvent, TotalLocked,
+};
+use frame_support::{assert_err, assert_noop, assert_ok, traits::Get, StorageMap};
+use iroha_client_no_std::prelude::AssetDefinitionId;
+use quickcheck::{quickcheck, TestResult};
+use sp_runtime::DispatchError;
 
 // use mocktopus::mocking::*;
 /// Total supply
@@ -10,9 +15,10 @@ fn test_total_supply_correct() {
     run_test(|| {
         // initial supply
         let desired_total_supply = ALICE_BALANCE + BOB_BALANCE;
-        let total_supply = Treasury::get_total_supply();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(desired_total_supply, total_supply);
+        assert_eq!(Treasury::get_total_supply(DOT).unwrap(), 0);
     })
 }
 
@@ -22,20 +28,20 @@ fn test_transfer_succeeds() {
     run_test(|| {
         let sender = Origin::signed(ALICE);
         let receiver = BOB;
-        let amount: Balance = 3;
+        let amount = 3;
 
-        let init_balance_alice = Treasury::get_balance_from_account(ALICE);
-        let init_balance_bob = Treasury::get_balance_from_account(BOB);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance_alice = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_balance_bob = Treasury::get_balance_from_account(BOB, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
-        assert_ok!(Treasury::transfer(sender, receiver, amount));
-        let transfer_event = TestEvent::test_events(RawEvent::Transfer(ALICE, BOB, amount));
+        assert_ok!(Treasury::transfer(sender, XOR, receiver, amount));
+        let transfer_event = TestEvent::test_events(RawEvent::Transfer(ALICE, BOB, XOR, amount));
 
         assert!(System::events().iter().any(|a| a.event == transfer_event));
 
-        let balance_alice = Treasury::get_balance_from_account(ALICE);
-        let balance_bob = Treasury::get_balance_from_account(BOB);
-        let total_supply = Treasury::get_total_supply();
+        let balance_alice = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let balance_bob = Treasury::get_balance_from_account(BOB, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance_alice, init_balance_alice - amount);
         assert_eq!(balance_bob, init_balance_bob + amount);
@@ -50,18 +56,18 @@ fn test_transfer_fails() {
         let receiver = BOB;
         let amount = ALICE_BALANCE + 10;
 
-        let init_balance_alice = Treasury::get_balance_from_account(ALICE);
-        let init_balance_bob = Treasury::get_balance_from_account(BOB);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance_alice = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_balance_bob = Treasury::get_balance_from_account(BOB, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_err!(
-            Treasury::transfer(sender, receiver, amount),
+            Treasury::transfer(sender, XOR, receiver, amount),
             Error::InsufficientFunds
         );
 
-        let balance_alice = Treasury::get_balance_from_account(ALICE);
-        let balance_bob = Treasury::get_balance_from_account(BOB);
-        let total_supply = Treasury::get_total_supply();
+        let balance_alice = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let balance_bob = Treasury::get_balance_from_account(BOB, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance_alice, init_balance_alice);
         assert_eq!(balance_bob, init_balance_bob);
@@ -74,21 +80,26 @@ fn test_transfer_fails() {
 fn test_mint_succeeds() {
     run_test(|| {
         let requester = ALICE;
-        let amount: Balance = 5;
+        let amount = 5;
 
-        let init_balance_alice = Treasury::get_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance_alice = Treasury::get_balance_from_account(ALICE, DOT).unwrap();
+        let init_total_supply = Treasury::get_total_supply(DOT).unwrap();
 
-        Treasury::mint(requester, amount);
-        let mint_event = TestEvent::test_events(RawEvent::Mint(ALICE, amount));
+        assert_ok!(Treasury::mint(requester, DOT, amount));
+        let mint_event = TestEvent::test_events(RawEvent::Mint(ALICE, DOT, amount));
 
         assert!(System::events().iter().any(|a| a.event == mint_event));
 
-        let balance_alice = Treasury::get_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance_alice = Treasury::get_balance_from_account(ALICE, DOT).unwrap();
+        let total_supply = Treasury::get_total_supply(DOT).unwrap();
 
         assert_eq!(balance_alice, init_balance_alice + amount);
         assert_eq!(total_supply, init_total_supply + amount);
+        // other assets are not affected
+        assert_eq!(
+            Treasury::get_balance_from_account(ALICE, XOR).unwrap(),
+            ALICE_BALANCE
+        );
     })
 }
 
@@ -99,18 +110,18 @@ fn test_lock_succeeds() {
         let redeemer = ALICE;
         let amount = ALICE_BALANCE;
 
-        let init_balance = Treasury::get_balance_from_account(ALICE);
-        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
-        assert_ok!(Treasury::lock(redeemer, amount));
-        let lock_event = TestEvent::test_events(RawEvent::Lock(ALICE, amount));
+        assert_ok!(Treasury::lock(redeemer, XOR, amount, None));
+        let lock_event = TestEvent::test_events(RawEvent::Lock(ALICE, XOR, amount, None));
 
         assert!(System::events().iter().any(|a| a.event == lock_event));
 
-        let balance = Treasury::get_balance_from_account(ALICE);
-        let locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance, init_balance - amount);
         assert_eq!(locked_balance, init_locked_balance + amount);
@@ -124,15 +135,18 @@ fn test_lock_fails() {
         let redeemer = ALICE;
         let amount = ALICE_BALANCE + 5;
 
-        let init_balance = Treasury::get_balance_from_account(ALICE);
-        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
-        assert_err!(Treasury::lock(redeemer, amount), Error::InsufficientFunds);
+        assert_err!(
+            Treasury::lock(redeemer, XOR, amount, None).map_err(DispatchError::from),
+            Error::InsufficientFunds
+        );
 
-        let balance = Treasury::get_balance_from_account(ALICE);
-        let locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance, init_balance);
         assert_eq!(locked_balance, init_locked_balance);
@@ -140,6 +154,116 @@ fn test_lock_fails() {
     })
 }
 
+#[test]
+fn test_total_locked_per_asset() {
+    run_test(|| {
+        assert_ok!(Treasury::mint(BOB, DOT, 100));
+        assert_ok!(Treasury::lock(ALICE, XOR, 10, None));
+        assert_ok!(Treasury::lock(BOB, DOT, 30, None));
+        assert_ok!(Treasury::burn(BOB, DOT, 20, None));
+
+        assert_eq!(
+            Treasury::asset_supply(XOR),
+            Some(AssetSupply {
+                issued: ALICE_BALANCE + BOB_BALANCE,
+                locked: 10,
+                escrowed: 0,
+            })
+        );
+        assert_eq!(
+            Treasury::asset_supply(DOT),
+            Some(AssetSupply {
+                issued: 80,
+                locked: 10,
+                escrowed: 0,
+            })
+        );
+        assert_eq!(Treasury::asset_supply(42), None);
+    })
+}
+
+#[test]
+fn test_total_locked_overflow_fails() {
+    run_test(|| {
+        <TotalLocked<Test>>::insert(XOR, Balance::MAX);
+
+        assert_err!(
+            Treasury::lock(ALICE, XOR, 1, None).map_err(DispatchError::from),
+            Error::Overflow
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap(),
+            0
+        );
+
+        assert_ok!(Treasury::mint(ALICE, DOT, 1));
+        assert_ok!(Treasury::lock(ALICE, DOT, 1, None));
+        <TotalLocked<Test>>::insert(DOT, 0);
+        assert_err!(
+            Treasury::unlock(ALICE, DOT, 1, None).map_err(DispatchError::from),
+            Error::Underflow
+        );
+    })
+}
+
+/// Unlock
+#[test]
+fn test_unlock_succeeds() {
+    run_test(|| {
+        let amount = 10;
+
+        assert_ok!(Treasury::lock(ALICE, XOR, amount, None));
+        assert_err!(
+            Treasury::unlock(ALICE, XOR, amount + 1, None).map_err(DispatchError::from),
+            Error::InsufficientLockedFunds
+        );
+        assert_ok!(Treasury::unlock(ALICE, XOR, amount, None));
+        let unlock_event = TestEvent::test_events(RawEvent::Unlock(ALICE, XOR, amount, None));
+
+        assert!(System::events().iter().any(|a| a.event == unlock_event));
+        assert_eq!(
+            Treasury::get_balance_from_account(ALICE, XOR).unwrap(),
+            ALICE_BALANCE
+        );
+        assert_eq!(
+            Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap(),
+            0
+        );
+    })
+}
+
+#[test]
+fn test_locked_funds_are_held_in_escrow() {
+    run_test(|| {
+        let escrow = Treasury::escrow_account();
+        assert_ne!(escrow, ALICE);
+        assert_ne!(escrow, BOB);
+
+        assert_ok!(Treasury::lock(ALICE, XOR, 10, None));
+        assert_ok!(Treasury::lock(BOB, XOR, 5, None));
+        assert_eq!(Treasury::get_balance_from_account(escrow, XOR).unwrap(), 15);
+
+        assert_ok!(Treasury::unlock(ALICE, XOR, 10, None));
+        // the escrow still holds Bob's funds, but none of them are Alice's
+        assert_err!(
+            Treasury::unlock(ALICE, XOR, 1, None).map_err(DispatchError::from),
+            Error::InsufficientLockedFunds
+        );
+        assert_eq!(Treasury::get_balance_from_account(escrow, XOR).unwrap(), 5);
+
+        assert_ok!(Treasury::burn(BOB, XOR, 5, None));
+        assert_eq!(Treasury::get_balance_from_account(escrow, XOR).unwrap(), 0);
+        assert_eq!(
+            Treasury::get_balance_from_account(ALICE, XOR).unwrap(),
+            ALICE_BALANCE
+        );
+        assert_eq!(
+            Treasury::get_balance_from_account(BOB, XOR).unwrap(),
+            BOB_BALANCE - 5
+        );
+    })
+}
+
 /// Burn
 #[test]
 fn test_burn_succeeds() {
@@ -147,19 +271,19 @@ fn test_burn_succeeds() {
         let redeemer = ALICE;
         let amount = ALICE_BALANCE;
 
-        let init_balance = Treasury::get_balance_from_account(ALICE);
-        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
-        assert_ok!(Treasury::lock(redeemer, amount));
-        assert_ok!(Treasury::burn(redeemer, amount));
-        let burn_event = TestEvent::test_events(RawEvent::Burn(ALICE, amount));
+        assert_ok!(Treasury::lock(redeemer, XOR, amount, None));
+        assert_ok!(Treasury::burn(redeemer, XOR, amount, None));
+        let burn_event = TestEvent::test_events(RawEvent::Burn(ALICE, XOR, amount, None));
 
         assert!(System::events().iter().any(|a| a.event == burn_event));
 
-        let balance = Treasury::get_balance_from_account(ALICE);
-        let locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance, init_balance - amount);
         assert_eq!(locked_balance, init_locked_balance);
@@ -173,18 +297,18 @@ fn test_burn_fails() {
         let redeemer = ALICE;
         let amount = ALICE_BALANCE;
 
-        let init_balance = Treasury::get_balance_from_account(ALICE);
-        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_err!(
-            Treasury::burn(redeemer, amount),
+            Treasury::burn(redeemer, XOR, amount, None).map_err(DispatchError::from),
             Error::InsufficientLockedFunds
         );
 
-        let balance = Treasury::get_balance_from_account(ALICE);
-        let locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance, init_balance);
         assert_eq!(locked_balance, init_locked_balance);
@@ -199,19 +323,19 @@ fn test_burn_partially_succeeds() {
         let amount = ALICE_BALANCE;
         let burn_amount = amount - 10;
 
-        let init_balance = Treasury::get_balance_from_account(ALICE);
-        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let init_total_supply = Treasury::get_total_supply();
+        let init_balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let init_locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let init_total_supply = Treasury::get_total_supply(XOR).unwrap();
 
-        assert_ok!(Treasury::lock(redeemer, amount));
-        assert_ok!(Treasury::burn(redeemer, burn_amount));
-        let burn_event = TestEvent::test_events(RawEvent::Burn(ALICE, burn_amount));
+        assert_ok!(Treasury::lock(redeemer, XOR, amount, None));
+        assert_ok!(Treasury::burn(redeemer, XOR, burn_amount, None));
+        let burn_event = TestEvent::test_events(RawEvent::Burn(ALICE, XOR, burn_amount, None));
 
         assert!(System::events().iter().any(|a| a.event == burn_event));
 
-        let balance = Treasury::get_balance_from_account(ALICE);
-        let locked_balance = Treasury::get_locked_balance_from_account(ALICE);
-        let total_supply = Treasury::get_total_supply();
+        let balance = Treasury::get_balance_from_account(ALICE, XOR).unwrap();
+        let locked_balance = Treasury::get_locked_balance_from_account(ALICE, XOR).unwrap();
+        let total_supply = Treasury::get_total_supply(XOR).unwrap();
 
         assert_eq!(balance, init_balance - amount); // balance is locked
                                                     // part of the balance is still locked
@@ -219,3 +343,330 @@ fn test_burn_partially_succeeds() {
         assert_eq!(total_supply, init_total_supply - burn_amount);
     })
 }
+
+/// Registry
+#[test]
+fn test_native_asset_is_locked_and_released() {
+    run_test(|| {
+        let escrow = Treasury::escrow_account();
+        assert_eq!(
+            Treasury::asset_mode(NATIVE).unwrap(),
+            AssetMode::LockRelease
+        );
+
+        assert_ok!(Treasury::lock(ALICE, NATIVE, 100, None));
+        assert_eq!(
+            u128::from(Balances::free_balance(ALICE)),
+            ALICE_BALANCE - 100
+        );
+        assert_eq!(
+            Balances::free_balance(escrow),
+            100 + ExistentialDeposit::get()
+        );
+        assert_err!(
+            Treasury::burn(ALICE, NATIVE, 100, None).map_err(DispatchError::from),
+            Error::InvalidAssetMode
+        );
+        assert_err!(
+            Treasury::mint(ALICE, NATIVE, 100).map_err(DispatchError::from),
+            Error::InvalidAssetMode
+        );
+
+        assert_ok!(Treasury::escrow(ALICE, NATIVE, 100, Some(7)));
+        let escrow_event = TestEvent::test_events(RawEvent::Escrow(ALICE, NATIVE, 100, Some(7)));
+        assert!(System::events().iter().any(|a| a.event == escrow_event));
+        assert_err!(
+            Treasury::unlock(ALICE, NATIVE, 100, None).map_err(DispatchError::from),
+            Error::InsufficientLockedFunds
+        );
+
+        assert_err!(
+            Treasury::release(BOB, NATIVE, 101).map_err(DispatchError::from),
+            Error::InsufficientEscrowedFunds
+        );
+        assert_ok!(Treasury::release(BOB, NATIVE, 60));
+        assert_eq!(Treasury::get_balance_from_account(BOB, NATIVE).unwrap(), 60);
+        assert_eq!(
+            Treasury::asset_supply(NATIVE),
+            Some(AssetSupply {
+                issued: ALICE_BALANCE + u128::from(ExistentialDeposit::get()),
+                locked: 0,
+                escrowed: 40,
+            })
+        );
+    })
+}
+
+#[test]
+fn test_native_lock_below_existential_deposit() {
+    run_test(|| {
+        let escrow = Treasury::escrow_account();
+        let existential_deposit = u128::from(ExistentialDeposit::get());
+        let escrow_balance = || u128::from(Balances::free_balance(escrow));
+        assert_eq!(escrow_balance(), existential_deposit);
+
+        let amount = existential_deposit - 1;
+        assert_ok!(Treasury::lock(ALICE, NATIVE, amount, None));
+        assert_eq!(escrow_balance(), existential_deposit + amount);
+        assert_ok!(Treasury::escrow(ALICE, NATIVE, amount, None));
+        assert_err!(
+            Treasury::release(BOB, NATIVE, amount).map_err(DispatchError::from),
+            Error::BelowExistentialDeposit
+        );
+
+        // the escrow account survives releasing everything it holds
+        assert_ok!(Treasury::lock(ALICE, NATIVE, existential_deposit, None));
+        assert_ok!(Treasury::escrow(ALICE, NATIVE, existential_deposit, None));
+        assert_ok!(Treasury::release(BOB, NATIVE, amount + existential_deposit));
+        assert_eq!(escrow_balance(), existential_deposit);
+    })
+}
+
+#[test]
+fn test_asset_mode_is_respected() {
+    run_test(|| {
+        assert_ok!(Treasury::lock(ALICE, XOR, 10, None));
+        assert_err!(
+            Treasury::escrow(ALICE, XOR, 10, None).map_err(DispatchError::from),
+            Error::InvalidAssetMode
+        );
+        assert_err!(
+            Treasury::release(BOB, XOR, 10).map_err(DispatchError::from),
+            Error::InvalidAssetMode
+        );
+
+        // assets other than the native one can be lock-and-release as well
+        assert_ok!(Treasury::register_asset(
+            Origin::ROOT,
+            NATIVE + 1,
+            AssetDefinitionId::new("ROC", "rococo"),
+            b"ROC".to_vec(),
+            0,
+            0,
+            AssetMode::LockRelease
+        ));
+        assert_err!(
+            Treasury::mint(ALICE, NATIVE + 1, 10).map_err(DispatchError::from),
+            Error::InvalidAssetMode
+        );
+    })
+}
+
+#[test]
+fn test_register_asset_succeeds() {
+    run_test(|| {
+        let ksm = 2;
+        let definition_id = AssetDefinitionId::new("KSM", "kusama");
+
+        assert_noop!(
+            Treasury::register_asset(
+                Origin::signed(ALICE),
+                ksm,
+                definition_id.clone(),
+                b"KSM".to_vec(),
+                12,
+                0,
+                AssetMode::MintBurn
+            ),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(Treasury::register_asset(
+            Origin::ROOT,
+            ksm,
+            definition_id.clone(),
+            b"KSM".to_vec(),
+            12,
+            0,
+            AssetMode::MintBurn
+        ));
+        let register_event =
+            TestEvent::test_events(RawEvent::AssetRegistered(ksm, definition_id.clone()));
+
+        assert!(System::events().iter().any(|a| a.event == register_event));
+        assert_eq!(Treasury::asset_id(&definition_id).unwrap(), ksm);
+        assert_eq!(Treasury::definition_id(ksm).unwrap(), definition_id);
+        assert_ok!(Treasury::mint(ALICE, ksm, 7));
+        assert_eq!(Treasury::get_balance_from_account(ALICE, ksm).unwrap(), 7);
+
+        assert_err!(
+            Treasury::register_asset(
+                Origin::ROOT,
+                ksm + 1,
+                AssetDefinitionId::new("KSM", "kusama"),
+                b"KSM".to_vec(),
+                12,
+                0,
+                AssetMode::MintBurn
+            ),
+            Error::AssetAlreadyRegistered
+        );
+        assert_err!(
+            Treasury::register_asset(
+                Origin::ROOT,
+                XOR,
+                AssetDefinitionId::new("ROC", "rococo"),
+                b"ROC".to_vec(),
+                12,
+                0,
+                AssetMode::MintBurn
+            ),
+            Error::AssetAlreadyRegistered
+        );
+    })
+}
+
+#[test]
+fn test_unknown_asset_fails() {
+    run_test(|| {
+        let unknown = 42;
+
+        assert_err!(
+            Treasury::mint(ALICE, unknown, 1).map_err(DispatchError::from),
+            Error::UnknownAsset
+        );
+        assert_err!(
+            Treasury::transfer(Origin::signed(ALICE), unknown, BOB, 1),
+            Error::UnknownAsset
+        );
+        assert_err!(
+            Treasury::asset_id(&AssetDefinitionId::new("ROC", "rococo"))
+                .map_err(DispatchError::from),
+            Error::UnknownAsset
+        );
+    })
+}
+
+#[test]
+fn test_disabled_asset_fails() {
+    run_test(|| {
+        assert_ok!(Treasury::lock(ALICE, XOR, 10, None));
+        assert_ok!(Treasury::set_asset_enabled(Origin::ROOT, XOR, false));
+        let disable_event = TestEvent::test_events(RawEvent::AssetEnabled(XOR, false));
+
+        assert!(System::events().iter().any(|a| a.event == disable_event));
+        assert_err!(
+            Treasury::transfer(Origin::signed(ALICE), XOR, BOB, 1),
+            Error::AssetDisabled
+        );
+        assert_err!(
+            Treasury::mint(ALICE, XOR, 1).map_err(DispatchError::from),
+            Error::AssetDisabled
+        );
+        assert_err!(
+            Treasury::lock(ALICE, XOR, 1, None).map_err(DispatchError::from),
+            Error::AssetDisabled
+        );
+        // already locked funds can still be settled
+        assert_ok!(Treasury::unlock(ALICE, XOR, 5, None));
+        assert_ok!(Treasury::burn(ALICE, XOR, 5, None));
+
+        assert_ok!(Treasury::set_asset_enabled(Origin::ROOT, XOR, true));
+        assert_ok!(Treasury::transfer(Origin::signed(ALICE), XOR, BOB, 1));
+    })
+}
+
+/// Decimals conversion
+#[test]
+fn test_iroha_quantity_conversion() {
+    run_test(|| {
+        // DOT has 10 decimals on this chain and 4 on Iroha
+        assert_eq!(
+            Treasury::from_iroha_quantity(DOT, 12_345).unwrap(),
+            12_345_000_000
+        );
+        assert_eq!(
+            Treasury::to_iroha_quantity(DOT, 12_345_000_000).unwrap(),
+            12_345
+        );
+        assert_err!(
+            Treasury::to_iroha_quantity(DOT, 12_345_000_001).map_err(DispatchError::from),
+            Error::PrecisionLoss
+        );
+        assert_eq!(Treasury::to_iroha_quantity(XOR, 7).unwrap(), 7);
+        assert_err!(
+            Treasury::from_iroha_quantity(42, 1).map_err(DispatchError::from),
+            Error::UnknownAsset
+        );
+    })
+}
+
+#[test]
+fn test_convert_decimals_bounds() {
+    assert_eq!(
+        convert_decimals::<Test>(u128::MAX, 0, 0).unwrap(),
+        u128::MAX
+    );
+    assert!(convert_decimals::<Test>(u128::MAX, 0, 1).is_err());
+    assert_eq!(convert_decimals::<Test>(1, 0, 38).unwrap(), 10u128.pow(38));
+    assert!(convert_decimals::<Test>(1, 0, 39).is_err());
+    assert_eq!(convert_decimals::<Test>(0, 0, 255).unwrap(), 0);
+    assert_eq!(convert_decimals::<Test>(0, 255, 0).unwrap(), 0);
+    assert!(convert_decimals::<Test>(u128::MAX, 255, 0).is_err());
+}
+
+#[test]
+fn test_balance_conversion_bounds() {
+    let above_u32 = u128::from(u32::MAX) + 1;
+    assert_eq!(
+        num_to_balance::<Test>(above_u32).unwrap(),
+        above_u32 as Balance
+    );
+    assert_eq!(
+        num_to_balance::<Test>(u128::from(u64::MAX)).unwrap(),
+        u64::MAX
+    );
+    assert!(num_to_balance::<Test>(u128::from(u64::MAX) + 1).is_err());
+    assert!(num_to_balance::<Test>(u128::MAX).is_err());
+    assert_eq!(
+        balance_to_num::<Test>(u64::MAX).unwrap(),
+        u128::from(u64::MAX)
+    );
+}
+
+#[test]
+fn test_transfer_above_u32_succeeds() {
+    run_test(|| {
+        let amount = u128::from(u32::MAX) + 1;
+        assert_ok!(Treasury::mint(ALICE, XOR, amount));
+        assert_ok!(Treasury::transfer(Origin::signed(ALICE), XOR, BOB, amount));
+        assert_eq!(
+            Treasury::get_balance_from_account(BOB, XOR).unwrap(),
+            BOB_BALANCE + amount
+        );
+        assert_noop!(
+            Treasury::transfer(Origin::signed(ALICE), XOR, BOB, u128::from(u64::MAX) + 1),
+            Error::InvalidBalanceType
+        );
+    })
+}
+
+#[test]
+fn prop_scaling_up_round_trips() {
+    fn prop(amount: u64, from: u8, up: u8) -> TestResult {
+        let (from, to) = (from % 24, from % 24 + up % 24);
+        let scaled = match convert_decimals::<Test>(amount.into(), from, to) {
+            Ok(scaled) => scaled,
+            Err(_) => return TestResult::discard(),
+        };
+        TestResult::from_bool(
+            convert_decimals::<Test>(scaled, to, from).ok() == Some(u128::from(amount)),
+        )
+    }
+    quickcheck(prop as fn(u64, u8, u8) -> TestResult);
+}
+
+#[test]
+fn prop_scaling_down_is_exact_or_rejected() {
+    fn prop(amount: u128, to: u8, down: u8) -> bool {
+        let (from, to) = (to % 24 + down % 24, to % 24);
+        let factor = 10u128.pow(u32::from(from - to));
+        match convert_decimals::<Test>(amount, from, to) {
+            Ok(scaled) => {
+                amount % factor == 0
+                    && convert_decimals::<Test>(scaled, to, from).ok() == Some(amount)
+            }
+            Err(_) => amount % factor != 0,
+        }
+    }
+    quickcheck(prop as fn(u128, u8, u8) -> bool);
+}
@@ -8,7 +8,6 @@
 #[cfg(feature = "std")]
 include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 
-use frame_support::traits::StorageMapShim;
 use grandpa::fg_primitives;
 use grandpa::{AuthorityId as GrandpaId, AuthorityList as GrandpaAuthorityList};
 use sp_api::impl_runtime_apis;
@@ -243,62 +242,15 @@ impl balances::Trait for Runtime {
     type AccountStore = System;
 }
 
-/// XOR
-impl balances::Trait<balances::Instance1> for Runtime {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = Event;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        balances::Account<Runtime, balances::Instance1>,
-        system::CallOnCreatedAccount<Runtime>,
-        system::CallKillAccount<Runtime>,
-        AccountId,
-        balances::AccountData<Balance>,
-    >;
-}
-
-/// DOT
-impl balances::Trait<balances::Instance2> for Runtime {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = Event;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        balances::Account<Runtime, balances::Instance1>,
-        system::CallOnCreatedAccount<Runtime>,
-        system::CallKillAccount<Runtime>,
-        AccountId,
-        balances::AccountData<Balance>,
-    >;
-}
-
-/// KSM
-impl balances::Trait<balances::Instance3> for Runtime {
-    /// The type for recording an account's balance.
-    type Balance = Balance;
-    /// The ubiquitous event type.
-    type Event = Event;
-    type DustRemoval = ();
-    type ExistentialDeposit = ExistentialDeposit;
-    type AccountStore = StorageMapShim<
-        balances::Account<Runtime, balances::Instance1>,
-        system::CallOnCreatedAccount<Runtime>,
-        system::CallKillAccount<Runtime>,
-        AccountId,
-        balances::AccountData<Balance>,
-    >;
+parameter_types! {
+    pub const NativeAssetId: treasury::AssetId = 3;
 }
 
 impl treasury::Trait for Runtime {
+    type Balance = Balance;
+    type Currency = Balances;
+    type NativeAssetId = NativeAssetId;
     type Event = Event;
-    type XOR = balances::Module<Runtime, balances::Instance1>;
-    type DOT = balances::Module<Runtime, balances::Instance2>;
-    type KSM = balances::Module<Runtime, balances::Instance3>;
 }
 
 impl<T: SigningTypes> system::offchain::SignMessage<T> for Runtime {
@@ -395,6 +347,12 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 
 parameter_types! {
     pub const UnsignedPriority: u64 = 100;
+    pub const OutgoingTransferTimeout: BlockNumber = 10 * MINUTES;
+    pub const MaxOutgoingTransferRetries: u32 = 3;
+    pub const IncomingAttestationTimeout: BlockNumber = 1 * HOURS;
+    pub const OversizedTransfers: iroha_bridge::OversizedTransferPolicy =
+        iroha_bridge::OversizedTransferPolicy::Reject;
+    pub const Ss58Prefix: u8 = 42;
 }
 
 /// Used for the module template in `./template.rs`
@@ -404,6 +362,11 @@ impl iroha_bridge::Trait for Runtime {
     type Call = Call;
     type Event = Event;
     type UnsignedPriority = UnsignedPriority;
+    type OutgoingTransferTimeout = OutgoingTransferTimeout;
+    type MaxOutgoingTransferRetries = MaxOutgoingTransferRetries;
+    type IncomingAttestationTimeout = IncomingAttestationTimeout;
+    type OversizedTransferPolicy = OversizedTransfers;
+    type Ss58Prefix = Ss58Prefix;
 }
 
 construct_runtime!(
@@ -420,10 +383,7 @@ construct_runtime!(
         Balances: balances::{Module, Call, Storage, Config<T>, Event<T>},
         TransactionPayment: transaction_payment::{Module, Storage},
         Sudo: sudo::{Module, Call, Config<T>, Storage, Event<T>},
-        XOR: balances::<Instance1>::{Module, Call, Storage, Config<T>, Event<T>},
-        DOT: balances::<Instance2>::{Module, Call, Storage, Config<T>, Event<T>},
-        KSM: balances::<Instance3>::{Module, Call, Storage, Config<T>, Event<T>},
-        Treasury: treasury::{Module, Call, Storage, Event<T>},
+        Treasury: treasury::{Module, Call, Storage, Config<T>, Event<T>},
         IrohaBridge: iroha_bridge::{Module, Call, Storage, Config<T>, Event<T>},
     }
 );
@@ -525,6 +485,16 @@ impl_runtime_apis! {
         }
     }
 
+    impl treasury::TreasuryApi<Block, AccountId> for Runtime {
+        fn asset_supply(asset_id: treasury::AssetId) -> Option<treasury::AssetSupply> {
+            Treasury::asset_supply(asset_id)
+        }
+
+        fn escrow_account() -> AccountId {
+            Treasury::escrow_account()
+        }
+    }
+
     impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
         fn slot_duration() -> u64 {
             Aura::slot_duration()